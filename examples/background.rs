@@ -6,67 +6,15 @@ extern crate orbimage;
 use std::env;
 
 use orbclient::{Color, EventOption, Renderer, Window, WindowFlag};
-use orbimage::Image;
-
-#[derive(Clone, Copy, Debug)]
-enum BackgroundMode {
-    /// Do not resize the image, just center it
-    Center,
-    /// Resize the image to the display size
-    Fill,
-    /// Resize the image - keeping its aspect ratio, and fit it to the display with blank space
-    Scale,
-    /// Resize the image - keeping its aspect ratio, and crop to remove all blank space
-    Zoom,
-}
-
-impl BackgroundMode {
-    fn from_str(string: &str) -> BackgroundMode {
-        match string {
-            "fill" => BackgroundMode::Fill,
-            "scale" => BackgroundMode::Scale,
-            "zoom" => BackgroundMode::Zoom,
-            _ => BackgroundMode::Center
-        }
-    }
-}
-
-fn find_scale(image: &Image, mode: BackgroundMode, display_width: u32, display_height: u32) -> (u32, u32) {
-    match mode {
-        BackgroundMode::Center => {
-            (image.width(), image.height())
-        },
-        BackgroundMode::Fill => {
-            (display_width, display_height)
-        },
-        BackgroundMode::Scale => {
-            let d_w = display_width as f64;
-            let d_h = display_height as f64;
-            let i_w = image.width() as f64;
-            let i_h = image.height() as f64;
-
-            let scale = if d_w / d_h > i_w / i_h {
-                d_h / i_h
-            } else {
-                d_w / i_w
-            };
-
-            ((i_w * scale) as u32, (i_h * scale) as u32)
-        },
-        BackgroundMode::Zoom => {
-            let d_w = display_width as f64;
-            let d_h = display_height as f64;
-            let i_w = image.width() as f64;
-            let i_h = image.height() as f64;
-
-            let scale = if d_w / d_h < i_w / i_h {
-                d_h / i_h
-            } else {
-                d_w / i_w
-            };
-
-            ((i_w * scale) as u32, (i_h * scale) as u32)
-        }
+use orbimage::{Image, Placement, Resizer, ScaleMode};
+
+fn scale_mode(string: &str) -> ScaleMode {
+    match string {
+        "fill" => ScaleMode::Fill,
+        "scale" => ScaleMode::Scale,
+        "zoom" => ScaleMode::Zoom,
+        "tile" => ScaleMode::Tile,
+        _ => ScaleMode::Center
     }
 }
 
@@ -78,7 +26,7 @@ fn main() {
         None => "/ui/background.png".to_string(),
     };
 
-    let mode = BackgroundMode::from_str(&args.next().unwrap_or(String::new()));
+    let mode = scale_mode(&args.next().unwrap_or_default());
 
     match Image::from_path(&path) {
         Ok(image) => {
@@ -89,42 +37,34 @@ fn main() {
                 &[WindowFlag::Back, WindowFlag::Borderless, WindowFlag::Unclosable]
             ).unwrap();
 
-            let mut scaled_image = image.clone();
+            // The resizer and scaled image are reused for as long as the scaled size stays the same
+            let mut resizer: Option<Resizer> = None;
+            let mut scaled_image = Image::default();
+            let mut scaled: Option<((u32, u32), Placement)> = None;
             let mut resize = Some((display_width, display_height));
             loop {
                 if let Some((w, h)) = resize.take() {
-                    let (width, height) = find_scale(&image, mode, w, h);
-
-                    if width == scaled_image.width() && height == scaled_image.height() {
-                        // Do not resize scaled image
-                    } else if width == image.width() && height == image.height() {
-                        scaled_image = image.clone();
-                    } else {
-                        scaled_image = image.resize(width, height, orbimage::ResizeType::Lanczos3).unwrap();
-                    }
-
-                    let (crop_x, crop_w) = if width > w  {
-                        ((width - w)/2, w)
-                    } else {
-                        (0, width)
-                    };
-
-                    let (crop_y, crop_h) = if height > h {
-                        ((height - h)/2, h)
-                    } else {
-                        (0, height)
+                    // Do not resize scaled image for the same window size
+                    let place = match scaled {
+                        Some((size, place)) if size == (w, h) => place,
+                        _ => {
+                            let place = image.scale_into(
+                                w, h, mode, orbimage::ResizeType::Lanczos3,
+                                &mut resizer, &mut scaled_image
+                            ).unwrap();
+                            scaled = Some(((w, h), place));
+                            place
+                        }
                     };
 
                     window.set(Color::rgb(0, 0, 0));
 
-                    let x = (w as i32 - crop_w as i32)/2;
-                    let y = (h as i32 - crop_h as i32)/2;
                     scaled_image.roi(
-                        crop_x, crop_y,
-                        crop_w, crop_h,
+                        place.crop_x, place.crop_y,
+                        place.w, place.h,
                     ).draw(
                         &mut window,
-                        x, y
+                        place.x, place.y
                     );
 
                     window.sync();
@@ -5,27 +5,20 @@ extern crate orbimage;
 
 use std::cmp::max;
 use std::env;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use orbclient::{Color, EventOption, Renderer, Window, WindowFlag};
-use orbimage::Image;
+use orbimage::{Animation, Image, LoopCount, Resizer, ScaleMode};
 
+/// Fit the image to the size, without enlarging it
 fn find_scale(image: &Image, width: u32, height: u32) -> (u32, u32, f64) {
-    let d_w = width as f64;
-    let d_h = height as f64;
-    let i_w = image.width() as f64;
-    let i_h = image.height() as f64;
-
-    let scale = if d_w / d_h > i_w / i_h {
-        d_h / i_h
-    } else {
-        d_w / i_w
-    };
-
-    if scale < 1.0 {
-        ((i_w * scale) as u32, (i_h * scale) as u32, scale)
-    } else {
-        (i_w as u32, i_h as u32, 1.0)
+    if image.width() <= width && image.height() <= height {
+        return (image.width(), image.height(), 1.0);
     }
+
+    let (w, h) = ScaleMode::Scale.scaled_size(image.width(), image.height(), width, height);
+    (w, h, w as f64 / image.width() as f64)
 }
 
 fn draw_image(window: &mut Window, image: &Image) {
@@ -57,35 +50,78 @@ fn main() {
         None => "/ui/background.png".to_string(),
     };
 
-    match Image::from_path(&path) {
-        Ok(image) => {
+    match Animation::from_path(&path) {
+        Ok(animation) => {
+            let frames: Vec<Image> = animation.frames().iter().map(|frame| frame.image().clone()).collect();
+            let delays: Vec<Duration> = animation.frames().iter().map(|frame| frame.delay()).collect();
+            let image = &frames[0];
+            let animated = frames.len() > 1;
+
             let (display_width, display_height) = orbclient::get_display_size().expect("viewer: failed to get display size");
 
-            let (width, height, scale) = find_scale(&image, display_width * 4/5, display_height * 4/5);
+            let (width, height, scale) = find_scale(image, display_width * 4/5, display_height * 4/5);
+
+            let mut flags = vec![WindowFlag::Resizable];
+            if animated {
+                // Poll events so frames keep advancing while idle
+                flags.push(WindowFlag::Async);
+            }
 
             let mut window = Window::new_flags(
                 -1, -1, max(320, width), max(240, height),
                 &format!("{} - {:.1}% - Viewer", path, scale * 100.0),
-                &[WindowFlag::Resizable]
+                &flags
             ).unwrap();
 
-            let mut scaled_image = image.clone();
+            let mut scaled_frames = frames.clone();
+            let mut frame_index = 0;
+            let mut plays = 1;
+            let mut next_frame = Instant::now() + delays[0];
             let mut resize = Some((window.width(), window.height()));
             loop {
+                let mut redraw = false;
+
                 if let Some((w, h)) = resize.take() {
-                    let (width, height, scale) = find_scale(&image, w, h);
+                    let (width, height, scale) = find_scale(image, w, h);
 
-                    if width == scaled_image.width() && height == scaled_image.height() {
-                        // Do not resize scaled image
+                    if width == scaled_frames[0].width() && height == scaled_frames[0].height() {
+                        // Do not resize scaled frames
                     } else if width == image.width() && height == image.height() {
-                        scaled_image = image.clone();
+                        scaled_frames = frames.clone();
                     } else {
-                        scaled_image = image.resize(width, height, orbimage::ResizeType::Lanczos3).unwrap();
+                        // Plan the filter once and reuse it for every frame
+                        let mut resizer = Resizer::new(
+                            image.width(), image.height(),
+                            width, height,
+                            orbimage::ResizeType::Lanczos3
+                        ).unwrap();
+                        for (frame, scaled_frame) in frames.iter().zip(scaled_frames.iter_mut()) {
+                            resizer.resize_into(frame, scaled_frame).unwrap();
+                        }
                     }
 
                     window.set_title(&format!("{} - {:.1}% - Viewer", path, scale * 100.0));
 
-                    draw_image(&mut window, &scaled_image);
+                    redraw = true;
+                }
+
+                let playing = match animation.loop_count() {
+                    LoopCount::Infinite => true,
+                    LoopCount::Finite(count) => plays < count || frame_index + 1 < frames.len(),
+                };
+                if animated && playing && Instant::now() >= next_frame {
+                    frame_index += 1;
+                    if frame_index == frames.len() {
+                        frame_index = 0;
+                        plays += 1;
+                    }
+                    // Treat very short delays the way browsers do
+                    next_frame += max(delays[frame_index], Duration::from_millis(20));
+                    redraw = true;
+                }
+
+                if redraw {
+                    draw_image(&mut window, &scaled_frames[frame_index]);
                 }
 
                 for event in window.events() {
@@ -97,6 +133,10 @@ fn main() {
                         _ => ()
                     }
                 }
+
+                if animated {
+                    thread::sleep(Duration::from_millis(5));
+                }
             }
         },
         Err(err) => {
@@ -0,0 +1,142 @@
+use Image;
+
+/// A 4x5 matrix transforming colors as `[r, g, b, a, 1]` column vectors, with rows for
+/// red, green, blue and alpha. Channels are between 0 and 1, so offsets in the last column
+/// are too, as in SVG's feColorMatrix
+pub type ColorMatrix = [[f32; 5]; 4];
+
+/// Weights of red, green and blue in perceived luminance, from Rec. 709
+pub(crate) const LUMA: [f32; 3] = [0.2126, 0.7152, 0.0722];
+
+impl Image {
+    /// Lighten or darken the image by adding amount, between -1 and 1, to every channel
+    pub fn brighten(&mut self, amount: f32) {
+        self.color_matrix(&[
+            [1.0, 0.0, 0.0, 0.0, amount],
+            [0.0, 1.0, 0.0, 0.0, amount],
+            [0.0, 0.0, 1.0, 0.0, amount],
+            [0.0, 0.0, 0.0, 1.0, 0.0],
+        ]);
+    }
+
+    /// Scale the distance of every channel from middle gray. 1 leaves the image unchanged,
+    /// 0 makes it flat gray and values above 1 increase contrast
+    pub fn contrast(&mut self, amount: f32) {
+        let offset = 0.5 - 0.5 * amount;
+        self.color_matrix(&[
+            [amount, 0.0, 0.0, 0.0, offset],
+            [0.0, amount, 0.0, 0.0, offset],
+            [0.0, 0.0, amount, 0.0, offset],
+            [0.0, 0.0, 0.0, 1.0, 0.0],
+        ]);
+    }
+
+    /// Apply a gamma curve, raising every channel to the power `1 / gamma`.
+    /// Values above 1 lighten the midtones, values below 1 darken them
+    pub fn gamma(&mut self, gamma: f32) {
+        if gamma.is_nan() || gamma <= 0.0 {
+            return;
+        }
+
+        let mut table = [0u8; 256];
+        for (i, value) in table.iter_mut().enumerate() {
+            *value = ((i as f32 / 255.0).powf(1.0 / gamma) * 255.0 + 0.5) as u8;
+        }
+
+        self.with_straight(|image| {
+            for pixel in image.data.iter_mut() {
+                let [b, g, r, a] = pixel.data.to_le_bytes();
+                pixel.data = u32::from_le_bytes([table[b as usize], table[g as usize], table[r as usize], a]);
+            }
+        });
+    }
+
+    /// Rotate the hue of every pixel by an angle in degrees, keeping luminance
+    pub fn hue_rotate(&mut self, degrees: f32) {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let [lr, lg, lb] = LUMA;
+        self.color_matrix(&[
+            [
+                lr + cos * (1.0 - lr) - sin * lr,
+                lg - cos * lg - sin * lg,
+                lb - cos * lb + sin * (1.0 - lb),
+                0.0,
+                0.0,
+            ],
+            [
+                lr - cos * lr + sin * 0.143,
+                lg + cos * (1.0 - lg) + sin * 0.140,
+                lb - cos * lb - sin * 0.283,
+                0.0,
+                0.0,
+            ],
+            [
+                lr - cos * lr - sin * (1.0 - lr),
+                lg - cos * lg + sin * lg,
+                lb + cos * (1.0 - lb) + sin * lb,
+                0.0,
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0, 0.0],
+        ]);
+    }
+
+    /// Scale the saturation of the image. 1 leaves the image unchanged, 0 makes it
+    /// grayscale and values above 1 make colors more vivid
+    pub fn saturate(&mut self, amount: f32) {
+        let [lr, lg, lb] = LUMA;
+        let keep = |luma: f32| luma * (1.0 - amount) + amount;
+        let mix = |luma: f32| luma * (1.0 - amount);
+        self.color_matrix(&[
+            [keep(lr), mix(lg), mix(lb), 0.0, 0.0],
+            [mix(lr), keep(lg), mix(lb), 0.0, 0.0],
+            [mix(lr), mix(lg), keep(lb), 0.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0, 0.0],
+        ]);
+    }
+
+    /// Invert the colors of the image, keeping alpha
+    pub fn invert(&mut self) {
+        self.with_straight(|image| {
+            for pixel in image.data.iter_mut() {
+                pixel.data ^= 0x00FFFFFF;
+            }
+        });
+    }
+
+    /// Replace every color with its luminance
+    pub fn grayscale(&mut self) {
+        self.saturate(0.0);
+    }
+
+    /// Give the image the brown tones of an old photograph
+    pub fn sepia(&mut self) {
+        self.color_matrix(&[
+            [0.393, 0.769, 0.189, 0.0, 0.0],
+            [0.349, 0.686, 0.168, 0.0, 0.0],
+            [0.272, 0.534, 0.131, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0, 0.0],
+        ]);
+    }
+
+    /// Transform every pixel by a color matrix. Colors are straight, not premultiplied
+    /// by alpha, and results are clamped between 0 and 1
+    pub fn color_matrix(&mut self, matrix: &ColorMatrix) {
+        let byte = |c: f32| (c * 255.0 + 0.5).clamp(0.0, 255.0) as u8;
+        self.with_straight(|image| {
+            for pixel in image.data.iter_mut() {
+                let [b, g, r, a] = pixel.data.to_le_bytes();
+                let input = [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, a as f32 / 255.0];
+                let row = |row: &[f32; 5]| {
+                    row[0] * input[0] + row[1] * input[1] + row[2] * input[2] + row[3] * input[3] + row[4]
+                };
+                pixel.data = u32::from_le_bytes([
+                    byte(row(&matrix[2])),
+                    byte(row(&matrix[1])),
+                    byte(row(&matrix[0])),
+                    byte(row(&matrix[3])),
+                ]);
+            }
+        });
+    }
+}
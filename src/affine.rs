@@ -0,0 +1,225 @@
+use std::ops::Mul;
+
+use orbclient::Color;
+
+use {AlphaMode, Image};
+
+/// A 2D affine transform, mapping `(x, y)` to `(a * x + b * y + c, d * x + e * y + f)`.
+/// Coordinates are in pixels with y pointing down, so positive rotations turn clockwise
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Affine {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Affine {
+    /// The transform that leaves points where they are
+    pub fn identity() -> Self {
+        Affine { a: 1.0, b: 0.0, c: 0.0, d: 0.0, e: 1.0, f: 0.0 }
+    }
+
+    /// Move points by x, y
+    pub fn translate(x: f64, y: f64) -> Self {
+        Affine { c: x, f: y, ..Affine::identity() }
+    }
+
+    /// Scale points away from the origin
+    pub fn scale(x: f64, y: f64) -> Self {
+        Affine { a: x, e: y, ..Affine::identity() }
+    }
+
+    /// Rotate points clockwise around the origin by an angle in degrees
+    pub fn rotate(degrees: f64) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Affine { a: cos, b: -sin, c: 0.0, d: sin, e: cos, f: 0.0 }
+    }
+
+    /// Shear points, moving x by `x` times y and y by `y` times x
+    pub fn shear(x: f64, y: f64) -> Self {
+        Affine { b: x, d: y, ..Affine::identity() }
+    }
+
+    /// Get the transform applying this one, then `next`
+    pub fn then(&self, next: &Affine) -> Affine {
+        *next * *self
+    }
+
+    /// Get the transform undoing this one, if it does not collapse points onto a line
+    pub fn invert(&self) -> Option<Affine> {
+        let det = self.a * self.e - self.b * self.d;
+        if det.abs() < 1e-12 || !det.is_finite() {
+            return None;
+        }
+
+        let a = self.e / det;
+        let b = -self.b / det;
+        let d = -self.d / det;
+        let e = self.a / det;
+        Some(Affine {
+            a,
+            b,
+            c: -(a * self.c + b * self.f),
+            d,
+            e,
+            f: -(d * self.c + e * self.f),
+        })
+    }
+
+    /// Transform a point
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (self.a * x + self.b * y + self.c, self.d * x + self.e * y + self.f)
+    }
+}
+
+impl Mul for Affine {
+    type Output = Affine;
+
+    /// Compose transforms so that `rhs` is applied first, as with matrices
+    fn mul(self, rhs: Affine) -> Affine {
+        Affine {
+            a: self.a * rhs.a + self.b * rhs.d,
+            b: self.a * rhs.b + self.b * rhs.e,
+            c: self.a * rhs.c + self.b * rhs.f + self.c,
+            d: self.d * rhs.a + self.e * rhs.d,
+            e: self.d * rhs.b + self.e * rhs.e,
+            f: self.d * rhs.c + self.e * rhs.f + self.f,
+        }
+    }
+}
+
+/// How colors are read between pixel centers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Nearest pixel, like `ResizeType::Point`
+    Nearest,
+    /// Weighted average of the four nearest pixels, like `ResizeType::Triangle`
+    Bilinear,
+    /// Catmull-Rom spline through the sixteen nearest pixels, like `ResizeType::Catrom`
+    Bicubic,
+}
+
+impl Image {
+    /// Get an image of `out_w` by `out_h` pixels showing this image moved by a transform
+    /// from its coordinates to the output's. Areas outside the image are transparent,
+    /// and its edges blend smoothly into them unless sampling the nearest pixel
+    pub fn transform(&self, transform: &Affine, out_w: u32, out_h: u32, interpolation: Interpolation) -> Image {
+        let inverse = transform.invert();
+        self.map_pixels(out_w, out_h, interpolation, |x, y| inverse.map(|inverse| {
+            let (sx, sy) = inverse.apply(x, y);
+            (sx, sy, 1.0)
+        }))
+    }
+
+    /// Get a copy of the image rotated clockwise by an angle in degrees, around its center.
+    /// The canvas grows to fit the rotated corners
+    pub fn rotate(&self, degrees: f64, interpolation: Interpolation) -> Image {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let (w, h) = (self.w as f64, self.h as f64);
+        // Ignore rounding errors, so that right angles do not add a pixel
+        let fit = |len: f64| (len - 1e-6).ceil().max(0.0) as u32;
+        let out_w = fit(w * cos.abs() + h * sin.abs());
+        let out_h = fit(w * sin.abs() + h * cos.abs());
+
+        let transform = Affine::translate(-w / 2.0, -h / 2.0)
+            .then(&Affine::rotate(degrees))
+            .then(&Affine::translate(out_w as f64 / 2.0, out_h as f64 / 2.0));
+        self.transform(&transform, out_w, out_h, interpolation)
+    }
+
+    /// Build an image by sampling this one where `source` maps the center of each
+    /// output pixel to, scaling alpha by the coverage it returns along with the point.
+    /// Pixels are left transparent where `source` returns None
+    pub(crate) fn map_pixels<F: Fn(f64, f64) -> Option<(f64, f64, f32)>>(&self, out_w: u32, out_h: u32, interpolation: Interpolation, source: F) -> Image {
+        let premultiplied = self.alpha == AlphaMode::Premultiplied;
+        let mut data = Vec::with_capacity(out_w as usize * out_h as usize);
+        for y in 0..out_h {
+            for x in 0..out_w {
+                let color = match source(x as f64 + 0.5, y as f64 + 0.5) {
+                    Some((sx, sy, coverage)) => {
+                        let mut channels = self.sample(sx, sy, interpolation);
+                        for channel in channels.iter_mut() {
+                            *channel *= coverage;
+                        }
+                        to_color(channels, premultiplied)
+                    },
+                    None => Color { data: 0 },
+                };
+                data.push(color);
+            }
+        }
+        self.with_data(out_w, out_h, data.into_boxed_slice())
+    }
+
+    /// Read premultiplied B, G, R, A channels at a point, where pixel centers are at half coordinates
+    fn sample(&self, x: f64, y: f64, interpolation: Interpolation) -> [f32; 4] {
+        if !x.is_finite() || !y.is_finite() {
+            return [0.0; 4];
+        }
+        let (x, y) = (x - 0.5, y - 0.5);
+        match interpolation {
+            Interpolation::Nearest => self.premultiplied_at(x.round() as i64, y.round() as i64),
+            Interpolation::Bilinear => {
+                let (x0, y0) = (x.floor(), y.floor());
+                let (fx, fy) = ((x - x0) as f32, (y - y0) as f32);
+                self.weighted(x0 as i64, y0 as i64, &[1.0 - fx, fx], &[1.0 - fy, fy])
+            },
+            Interpolation::Bicubic => {
+                let (x0, y0) = (x.floor(), y.floor());
+                let (wx, wy) = (catmull_rom((x - x0) as f32), catmull_rom((y - y0) as f32));
+                self.weighted(x0 as i64 - 1, y0 as i64 - 1, &wx, &wy)
+            },
+        }
+    }
+
+    /// Sum pixels from the top left corner x, y with separable weights
+    fn weighted(&self, x: i64, y: i64, wx: &[f32], wy: &[f32]) -> [f32; 4] {
+        let mut sum = [0.0; 4];
+        for (j, wy) in wy.iter().enumerate() {
+            for (i, wx) in wx.iter().enumerate() {
+                let pixel = self.premultiplied_at(x + i as i64, y + j as i64);
+                for c in 0..4 {
+                    sum[c] += pixel[c] * wx * wy;
+                }
+            }
+        }
+        sum
+    }
+
+    /// Premultiplied channels of a pixel, transparent outside the image
+    fn premultiplied_at(&self, x: i64, y: i64) -> [f32; 4] {
+        if x < 0 || y < 0 || x >= self.w as i64 || y >= self.h as i64 {
+            return [0.0; 4];
+        }
+        let [b, g, r, a] = self.data[y as usize * self.w as usize + x as usize].data.to_le_bytes();
+        let scale = if self.alpha == AlphaMode::Premultiplied { 1.0 } else { a as f32 / 255.0 };
+        [b as f32 * scale, g as f32 * scale, r as f32 * scale, a as f32]
+    }
+}
+
+/// Weights of the four pixels around a point at fraction t between the middle two
+fn catmull_rom(t: f32) -> [f32; 4] {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    [
+        0.5 * (-t3 + 2.0 * t2 - t),
+        0.5 * (3.0 * t3 - 5.0 * t2 + 2.0),
+        0.5 * (-3.0 * t3 + 4.0 * t2 + t),
+        0.5 * (t3 - t2),
+    ]
+}
+
+/// Pack premultiplied channels, dividing by alpha unless the image stays premultiplied
+fn to_color(channels: [f32; 4], premultiplied: bool) -> Color {
+    let alpha = channels[3].clamp(0.0, 255.0);
+    if alpha < 0.5 {
+        return Color { data: 0 };
+    }
+    let scale = if premultiplied { 1.0 } else { 255.0 / alpha };
+    // Bicubic overshoot can leave colors brighter than alpha allows
+    let byte = |c: f32| (c.clamp(0.0, alpha) * scale + 0.5).min(255.0) as u8;
+    Color { data: u32::from_le_bytes([byte(channels[0]), byte(channels[1]), byte(channels[2]), (alpha + 0.5) as u8]) }
+}
@@ -0,0 +1,102 @@
+use std::cell::Cell;
+
+use orbclient::Color;
+
+use {Image, Result};
+
+/// How the color channels of an image relate to its alpha channel
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlphaMode {
+    /// Colors are stored independently of alpha, as windows expect them
+    Straight,
+    /// Colors are stored already multiplied by alpha, so that filtering and blending
+    /// do not bleed the color of transparent pixels into their neighbours.
+    ///
+    /// Resizing, blurring, compositing and convolution keep this representation. Drawing
+    /// converts a copy back to straight alpha, and so does encoding. Drawing *into* the
+    /// image with `Renderer` methods assumes straight colors
+    Premultiplied,
+}
+
+impl Image {
+    /// Create a new image from a boxed slice of colors already premultiplied by alpha
+    pub fn from_premultiplied_data(width: u32, height: u32, data: Box<[Color]>) -> Result<Self> {
+        let mut image = Image::from_data(width, height, data)?;
+        image.alpha = AlphaMode::Premultiplied;
+        Ok(image)
+    }
+
+    /// Get how the colors of the image are stored
+    pub fn alpha_mode(&self) -> AlphaMode {
+        self.alpha
+    }
+
+    /// Multiply colors by alpha, if they are not already
+    pub fn premultiply(&mut self) {
+        if self.alpha == AlphaMode::Straight {
+            for pixel in self.data.iter_mut() {
+                *pixel = premultiply(*pixel);
+            }
+            self.alpha = AlphaMode::Premultiplied;
+        }
+    }
+
+    /// Divide colors by alpha, if they are premultiplied
+    pub fn unpremultiply(&mut self) {
+        if self.alpha == AlphaMode::Premultiplied {
+            for pixel in self.data.iter_mut() {
+                *pixel = unpremultiply(*pixel);
+            }
+            self.alpha = AlphaMode::Straight;
+        }
+    }
+
+    /// Create an image with the size, drawing mode and alpha mode of this one
+    pub(crate) fn with_data(&self, w: u32, h: u32, data: Box<[Color]>) -> Image {
+        Image {
+            w,
+            h,
+            mode: Cell::new(self.mode.get()),
+            alpha: self.alpha,
+            data,
+        }
+    }
+
+    /// Run an operation that expects straight colors, converting around it if needed
+    pub(crate) fn with_straight<F: FnOnce(&mut Image)>(&mut self, f: F) {
+        let premultiplied = self.alpha == AlphaMode::Premultiplied;
+        self.unpremultiply();
+        f(self);
+        if premultiplied {
+            self.premultiply();
+        }
+    }
+
+    /// Get a straight alpha copy if the image is premultiplied
+    pub(crate) fn straight_copy(&self) -> Option<Image> {
+        if self.alpha == AlphaMode::Premultiplied {
+            let mut image = self.clone();
+            image.unpremultiply();
+            Some(image)
+        } else {
+            None
+        }
+    }
+}
+
+/// Multiply the color channels of a straight color by its alpha
+pub(crate) fn premultiply(color: Color) -> Color {
+    let [b, g, r, a] = color.data.to_le_bytes();
+    let mul = |c: u8| ((c as u32 * a as u32 + 127) / 255) as u8;
+    Color { data: u32::from_le_bytes([mul(b), mul(g), mul(r), a]) }
+}
+
+/// Divide the color channels of a premultiplied color by its alpha
+pub(crate) fn unpremultiply(color: Color) -> Color {
+    let [b, g, r, a] = color.data.to_le_bytes();
+    if a == 0 {
+        return Color { data: 0 };
+    }
+    let div = |c: u8| ((c as u32 * 255 + a as u32 / 2) / a as u32).min(255) as u8;
+    Color { data: u32::from_le_bytes([div(b), div(g), div(r), a]) }
+}
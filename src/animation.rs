@@ -0,0 +1,457 @@
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+use std::time::Duration;
+
+use gif::{self, SetParameter};
+use image::ImageError;
+use orbclient::Color;
+
+use probe::{self, PngChunks};
+use {CompositeOp, Error, Image, ImageFormat, Result};
+
+/// How the canvas region of a frame is treated before the next frame is drawn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisposeOp {
+    /// Leave the canvas as it is
+    None,
+    /// Clear the frame region to transparent black
+    Background,
+    /// Restore the frame region to what it was before the frame was drawn
+    Previous,
+}
+
+/// How a frame is combined with the canvas
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlendOp {
+    /// Replace the frame region, including alpha
+    Source,
+    /// Alpha blend the frame over the canvas
+    Over,
+}
+
+/// How many times an animation should be played
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoopCount {
+    Infinite,
+    Finite(u32),
+}
+
+/// A single frame of an animation, composited to the full canvas size
+#[derive(Clone)]
+pub struct Frame {
+    image: Image,
+    delay: Duration,
+    dispose: DisposeOp,
+    blend: BlendOp,
+}
+
+impl Frame {
+    /// The composited canvas for this frame
+    pub fn image(&self) -> &Image {
+        &self.image
+    }
+
+    /// Return the composited canvas for this frame
+    pub fn into_image(self) -> Image {
+        self.image
+    }
+
+    /// How long this frame is shown
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Disposal applied after this frame, as stored in the file
+    pub fn dispose(&self) -> DisposeOp {
+        self.dispose
+    }
+
+    /// Blending used for this frame, as stored in the file
+    pub fn blend(&self) -> BlendOp {
+        self.blend
+    }
+}
+
+/// A sequence of frames decoded from an animated GIF or APNG
+#[derive(Clone)]
+pub struct Animation {
+    w: u32,
+    h: u32,
+    frames: Vec<Frame>,
+    loop_count: LoopCount,
+}
+
+impl Animation {
+    /// Load an animation from file path. Still images load as a single frame, and formats
+    /// without magic bytes are recognized by the file extension
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let data = fs::read(path)?;
+        match ::path_format(&mut Cursor::new(&data), path)? {
+            ImageFormat::Gif => parse_gif(&data),
+            ImageFormat::Png => parse_apng(&data),
+            format => Ok(Self::from(Image::from_reader_with_format(Cursor::new(&data), format)?)),
+        }
+    }
+
+    /// Load an animation from encoded bytes in memory. Still images load as a single frame
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        match ImageFormat::from_magic(data) {
+            Some(ImageFormat::Gif) => parse_gif(data),
+            Some(ImageFormat::Png) => parse_apng(data),
+            _ => Ok(Self::from(Image::from_bytes(data)?)),
+        }
+    }
+
+    /// Width of the canvas in pixels
+    pub fn width(&self) -> u32 {
+        self.w
+    }
+
+    /// Height of the canvas in pixels
+    pub fn height(&self) -> u32 {
+        self.h
+    }
+
+    /// The composited frames
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// Return the composited frames
+    pub fn into_frames(self) -> Vec<Frame> {
+        self.frames
+    }
+
+    /// How many times the whole animation should be played
+    pub fn loop_count(&self) -> LoopCount {
+        self.loop_count
+    }
+
+    /// Total duration of a single play of the animation
+    pub fn duration(&self) -> Duration {
+        self.frames.iter().map(|frame| frame.delay).sum()
+    }
+}
+
+impl From<Image> for Animation {
+    /// Create a single frame animation from an image
+    fn from(image: Image) -> Self {
+        Animation {
+            w: image.w,
+            h: image.h,
+            frames: vec![Frame {
+                image,
+                delay: Duration::from_secs(0),
+                dispose: DisposeOp::None,
+                blend: BlendOp::Source,
+            }],
+            loop_count: LoopCount::Finite(1),
+        }
+    }
+}
+
+impl From<gif::DecodingError> for Error {
+    fn from(err: gif::DecodingError) -> Self {
+        Error::from(ImageError::from(err))
+    }
+}
+
+/// Canvas that frames are drawn onto, tracking what disposal needs to undo
+struct Compositor {
+    canvas: Image,
+    previous: Option<Image>,
+}
+
+impl Compositor {
+    fn new(width: u32, height: u32) -> Result<Self> {
+        Ok(Compositor {
+            canvas: Image::from_data(width, height, vec![Color { data: 0 }; ::pixel_count(width, height)?].into_boxed_slice())?,
+            previous: None,
+        })
+    }
+
+    /// Draw a frame region onto the canvas and return the composited frame.
+    /// Parts of the region outside the canvas are clipped
+    fn add(&mut self, region: &Image, x: u32, y: u32, delay: Duration, dispose: DisposeOp, blend: BlendOp) -> Frame {
+        if dispose == DisposeOp::Previous {
+            self.previous = Some(self.canvas.clone());
+        }
+
+        let stride = self.canvas.w as usize;
+        let (w, h) = if x < self.canvas.w && y < self.canvas.h {
+            (region.w.min(self.canvas.w - x) as usize, region.h.min(self.canvas.h - y) as usize)
+        } else {
+            (0, 0)
+        };
+        let rows = (0..h).map(|row| (y as usize + row) * stride + x as usize);
+
+        match blend {
+            BlendOp::Source => for (row, start) in rows.clone().enumerate() {
+                let src = &region.data[row * region.w as usize..][..w];
+                self.canvas.data[start..start + w].copy_from_slice(src);
+            },
+            BlendOp::Over => if w > 0 && h > 0 {
+                self.canvas.composite(region, x as i32, y as i32, CompositeOp::Over, 1.0);
+            },
+        }
+
+        let frame = Frame {
+            image: self.canvas.clone(),
+            delay,
+            dispose,
+            blend,
+        };
+
+        match dispose {
+            DisposeOp::None => (),
+            DisposeOp::Background => for start in rows {
+                for pixel in self.canvas.data[start..start + w].iter_mut() {
+                    *pixel = Color { data: 0 };
+                }
+            },
+            DisposeOp::Previous => if let Some(previous) = self.previous.take() {
+                self.canvas = previous;
+            },
+        }
+
+        frame
+    }
+}
+
+fn image_from_rgba(width: u32, height: u32, bytes: &[u8]) -> Result<Image> {
+    let data: Vec<Color> = bytes.chunks(4)
+        .map(|p| Color::rgba(p[0], p[1], p[2], p[3]))
+        .collect();
+    Image::from_data(width, height, data.into_boxed_slice())
+}
+
+fn parse_gif(data: &[u8]) -> Result<Animation> {
+    let mut decoder = gif::Decoder::new(data);
+    decoder.set(gif::ColorOutput::RGBA);
+    let mut reader = decoder.read_info()?;
+
+    let (width, height) = (reader.width() as u32, reader.height() as u32);
+    let mut compositor = Compositor::new(width, height)?;
+    let mut frames = Vec::new();
+    while let Some(frame) = reader.read_next_frame()? {
+        let region = image_from_rgba(frame.width as u32, frame.height as u32, &frame.buffer)?;
+        let dispose = match frame.dispose {
+            gif::DisposalMethod::Any | gif::DisposalMethod::Keep => DisposeOp::None,
+            gif::DisposalMethod::Background => DisposeOp::Background,
+            gif::DisposalMethod::Previous => DisposeOp::Previous,
+        };
+        // Delays are stored in hundredths of a second
+        let delay = Duration::from_millis(frame.delay as u64 * 10);
+        frames.push(compositor.add(&region, frame.left as u32, frame.top as u32, delay, dispose, BlendOp::Over));
+    }
+
+    if frames.is_empty() {
+        return Err(Error::Decode(ImageError::FormatError("GIF has no frames".to_string())));
+    }
+
+    Ok(Animation {
+        w: width,
+        h: height,
+        frames,
+        loop_count: gif_loop_count(data)?,
+    })
+}
+
+/// Read the repeat count from the NETSCAPE2.0 application extension, if present
+fn gif_loop_count(data: &[u8]) -> Result<LoopCount> {
+    let loop_count = match probe::probe_gif(data)?.1 {
+        // The extension counts repeats after the first play
+        Some(0) => LoopCount::Infinite,
+        Some(repeats) => LoopCount::Finite(repeats as u32 + 1),
+        None => LoopCount::Finite(1),
+    };
+    Ok(loop_count)
+}
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+struct Chunk {
+    kind: [u8; 4],
+    data: Vec<u8>,
+}
+
+/// Split a PNG file into its chunks, up to the end chunk
+fn png_chunks(data: &[u8]) -> Result<Vec<Chunk>> {
+    let mut reader = PngChunks::new(data)?;
+    let mut chunks = Vec::new();
+    loop {
+        let kind = reader.next_chunk()?;
+        if &kind == b"IEND" {
+            return Ok(chunks);
+        }
+        chunks.push(Chunk {
+            kind,
+            data: reader.read_data()?,
+        });
+    }
+}
+
+fn be_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+fn be_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([data[offset], data[offset + 1]])
+}
+
+/// Frame control values of an fcTL chunk
+struct FrameControl {
+    width: u32,
+    height: u32,
+    x: u32,
+    y: u32,
+    delay: Duration,
+    dispose: DisposeOp,
+    blend: BlendOp,
+}
+
+impl FrameControl {
+    fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() < 26 {
+            return Err(Error::Decode(ImageError::FormatError("fcTL chunk too short".to_string())));
+        }
+
+        let delay_num = be_u16(data, 20) as u64;
+        // A zero denominator means hundredths of a second
+        let delay_den = match be_u16(data, 22) {
+            0 => 100,
+            den => den as u64,
+        };
+
+        Ok(FrameControl {
+            width: be_u32(data, 4),
+            height: be_u32(data, 8),
+            x: be_u32(data, 12),
+            y: be_u32(data, 16),
+            delay: Duration::from_micros(delay_num * 1_000_000 / delay_den),
+            dispose: match data[24] {
+                1 => DisposeOp::Background,
+                2 => DisposeOp::Previous,
+                _ => DisposeOp::None,
+            },
+            blend: match data[25] {
+                1 => BlendOp::Over,
+                _ => BlendOp::Source,
+            },
+        })
+    }
+}
+
+/// Frame data collected while walking the APNG chunks
+struct PendingFrame {
+    control: FrameControl,
+    data: Vec<u8>,
+}
+
+fn parse_apng(data: &[u8]) -> Result<Animation> {
+    let chunks = png_chunks(data)?;
+
+    let ihdr = match chunks.first() {
+        Some(chunk) if &chunk.kind == b"IHDR" && chunk.data.len() == 13 => &chunk.data[..],
+        _ => return Err(Error::Decode(ImageError::FormatError("IHDR chunk missing".to_string()))),
+    };
+    let num_plays = match chunks.iter().find(|chunk| &chunk.kind == b"acTL") {
+        Some(actl) if actl.data.len() >= 8 => be_u32(&actl.data, 4),
+        // Without an animation control chunk this is a regular PNG
+        _ => return Ok(Animation::from(::parse_png(data)?)),
+    };
+
+    // Ancillary chunks before the image data apply to every frame
+    let shared: Vec<&Chunk> = chunks.iter()
+        .take_while(|chunk| &chunk.kind != b"IDAT")
+        .filter(|chunk| !matches!(&chunk.kind, b"IHDR" | b"acTL" | b"fcTL"))
+        .collect();
+
+    let mut pending = Vec::new();
+    let mut current: Option<PendingFrame> = None;
+    for chunk in chunks.iter() {
+        match &chunk.kind {
+            b"fcTL" => {
+                pending.extend(current.take());
+                current = Some(PendingFrame {
+                    control: FrameControl::parse(&chunk.data)?,
+                    data: Vec::new(),
+                });
+            },
+            // IDAT is only part of the animation if an fcTL precedes it
+            b"IDAT" => if let Some(ref mut frame) = current {
+                frame.data.extend_from_slice(&chunk.data);
+            },
+            b"fdAT" => if let Some(ref mut frame) = current {
+                frame.data.extend_from_slice(chunk.data.get(4..).unwrap_or(&[]));
+            },
+            _ => (),
+        }
+    }
+    pending.extend(current.take());
+
+    let (width, height) = (be_u32(ihdr, 0), be_u32(ihdr, 4));
+    let mut compositor = Compositor::new(width, height)?;
+    let mut frames = Vec::with_capacity(pending.len());
+    for (i, frame) in pending.into_iter().enumerate() {
+        let control = frame.control;
+        let png = standalone_png(ihdr, control.width, control.height, &shared, &frame.data);
+        let region = ::parse_png(&png)?;
+        // The first frame has nothing to restore, so previous disposal clears instead
+        let dispose = match control.dispose {
+            DisposeOp::Previous if i == 0 => DisposeOp::Background,
+            dispose => dispose,
+        };
+        frames.push(compositor.add(&region, control.x, control.y, control.delay, dispose, control.blend));
+    }
+
+    if frames.is_empty() {
+        return Err(Error::Decode(ImageError::FormatError("APNG has no frames".to_string())));
+    }
+
+    Ok(Animation {
+        w: width,
+        h: height,
+        frames,
+        loop_count: match num_plays {
+            0 => LoopCount::Infinite,
+            plays => LoopCount::Finite(plays),
+        },
+    })
+}
+
+/// Build a regular PNG file holding a single APNG frame, so it can be handed to the PNG decoder
+fn standalone_png(ihdr: &[u8], width: u32, height: u32, shared: &[&Chunk], data: &[u8]) -> Vec<u8> {
+    let mut header = ihdr.to_vec();
+    header[0..4].copy_from_slice(&width.to_be_bytes());
+    header[4..8].copy_from_slice(&height.to_be_bytes());
+
+    let mut png = PNG_SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    for chunk in shared {
+        write_chunk(&mut png, &chunk.kind, &chunk.data);
+    }
+    write_chunk(&mut png, b"IDAT", data);
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    png.extend_from_slice(&crc32(kind.iter().chain(data)).to_be_bytes());
+}
+
+fn crc32<'a, I: Iterator<Item = &'a u8>>(bytes: I) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
@@ -0,0 +1,153 @@
+use std::cmp;
+
+use orbclient::Color;
+
+use {AlphaMode, Image};
+
+/// How a source image is combined with the image below it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompositeOp {
+    /// Source drawn over the destination
+    Over,
+    /// Source kept only where the destination is opaque
+    In,
+    /// Source kept only where the destination is transparent
+    Out,
+    /// Source drawn over the destination, only where the destination is opaque
+    Atop,
+    /// Source and destination kept only where they do not overlap
+    Xor,
+    /// Colors multiplied, always darker
+    Multiply,
+    /// Inverted colors multiplied, always lighter
+    Screen,
+    /// Multiply or screen depending on the destination color
+    Overlay,
+    /// Darker of the two colors
+    Darken,
+    /// Lighter of the two colors
+    Lighten,
+    /// Soft darken or lighten depending on the source color
+    SoftLight,
+    /// Absolute difference of the colors
+    Difference,
+}
+
+impl CompositeOp {
+    /// Fractions of the source and destination kept, given their alphas
+    fn factors(self, src_a: f32, dst_a: f32) -> (f32, f32) {
+        match self {
+            CompositeOp::In => (dst_a, 0.0),
+            CompositeOp::Out => (1.0 - dst_a, 0.0),
+            CompositeOp::Atop => (dst_a, 1.0 - src_a),
+            CompositeOp::Xor => (1.0 - dst_a, 1.0 - src_a),
+            // Blend modes are composited with source over
+            _ => (1.0, 1.0 - src_a),
+        }
+    }
+
+    /// Mix one destination and source channel, or return the source for Porter-Duff operators
+    fn blend(self, dst: f32, src: f32) -> f32 {
+        match self {
+            CompositeOp::Multiply => dst * src,
+            CompositeOp::Screen => dst + src - dst * src,
+            CompositeOp::Overlay => if dst <= 0.5 {
+                2.0 * dst * src
+            } else {
+                let dst = 2.0 * dst - 1.0;
+                dst + src - dst * src
+            },
+            CompositeOp::Darken => dst.min(src),
+            CompositeOp::Lighten => dst.max(src),
+            CompositeOp::SoftLight => if src <= 0.5 {
+                dst - (1.0 - 2.0 * src) * dst * (1.0 - dst)
+            } else {
+                let d = if dst <= 0.25 {
+                    ((16.0 * dst - 12.0) * dst + 4.0) * dst
+                } else {
+                    dst.sqrt()
+                };
+                dst + (2.0 * src - 1.0) * (d - dst)
+            },
+            CompositeOp::Difference => (dst - src).abs(),
+            _ => src,
+        }
+    }
+}
+
+impl Image {
+    /// Combine another image into this one with its top left corner at x, y.
+    /// The source alpha is scaled by opacity, between 0 and 1. Pixels outside
+    /// the source rectangle are left unchanged, for every operator
+    pub fn composite(&mut self, src: &Image, x: i32, y: i32, op: CompositeOp, opacity: f32) {
+        let opacity = opacity.clamp(0.0, 1.0);
+
+        let x1 = cmp::max(x as i64, 0);
+        let y1 = cmp::max(y as i64, 0);
+        let x2 = cmp::min(x as i64 + src.w as i64, self.w as i64);
+        let y2 = cmp::min(y as i64 + src.h as i64, self.h as i64);
+        if x1 >= x2 || y1 >= y2 {
+            return;
+        }
+
+        let dst_premultiplied = self.alpha == AlphaMode::Premultiplied;
+        let src_premultiplied = src.alpha == AlphaMode::Premultiplied;
+        let dst_stride = self.w as usize;
+        let src_stride = src.w as usize;
+        let len = (x2 - x1) as usize;
+        for row in y1..y2 {
+            let dst_start = row as usize * dst_stride + x1 as usize;
+            let src_start = (row - y as i64) as usize * src_stride + (x1 - x as i64) as usize;
+            let dst_row = &mut self.data[dst_start..dst_start + len];
+            let src_row = &src.data[src_start..src_start + len];
+            for (dst, src) in dst_row.iter_mut().zip(src_row) {
+                let color = composite_pixel(
+                    channels(*dst, dst_premultiplied),
+                    channels(*src, src_premultiplied),
+                    op,
+                    opacity,
+                );
+                *dst = to_color(color, dst_premultiplied);
+            }
+        }
+    }
+}
+
+/// Combine straight B, G, R, A channels, returning straight channels
+fn composite_pixel(dst: [f32; 4], src: [f32; 4], op: CompositeOp, opacity: f32) -> [f32; 4] {
+    let [db, dg, dr, da] = dst;
+    let [sb, sg, sr, sa] = src;
+    let sa = sa * opacity;
+
+    // Premultiplied sums of the kept parts, divided by the resulting alpha
+    let (fa, fb) = op.factors(sa, da);
+    let alpha = sa * fa + da * fb;
+    if alpha <= 0.0 {
+        return [0.0; 4];
+    }
+
+    // The blended color replaces the source where the destination is opaque
+    let mix = |d: f32, s: f32| {
+        let s = (1.0 - da) * s + da * op.blend(d, s);
+        (s * sa * fa + d * da * fb) / alpha
+    };
+
+    [mix(db, sb), mix(dg, sg), mix(dr, sr), alpha]
+}
+
+/// Straight channels of a color as B, G, R, A between 0 and 1
+fn channels(color: Color, premultiplied: bool) -> [f32; 4] {
+    let [b, g, r, a] = color.data.to_le_bytes();
+    let alpha = a as f32 / 255.0;
+    let scale = if premultiplied && a > 0 { 1.0 / a as f32 } else { 1.0 / 255.0 };
+    [b as f32 * scale, g as f32 * scale, r as f32 * scale, alpha]
+}
+
+fn to_color(channels: [f32; 4], premultiplied: bool) -> Color {
+    let alpha = channels[3].clamp(0.0, 1.0);
+    let scale = if premultiplied { alpha } else { 1.0 };
+    let byte = |c: f32| (c.clamp(0.0, 1.0) * scale * 255.0 + 0.5) as u8;
+    Color {
+        data: u32::from_le_bytes([byte(channels[0]), byte(channels[1]), byte(channels[2]), (alpha * 255.0 + 0.5) as u8]),
+    }
+}
@@ -0,0 +1,228 @@
+use orbclient::Color;
+
+use {Error, Image, Result};
+
+/// How pixels beyond the edges of an image are read while filtering
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeMode {
+    /// Repeat the nearest edge pixel
+    Clamp,
+    /// Read from the opposite edge, as if the image were tiled
+    Wrap,
+    /// Reflect the image at its edges, without repeating the edge pixel
+    Mirror,
+    /// Treat pixels outside the image as transparent black
+    Transparent,
+}
+
+impl EdgeMode {
+    /// Map a coordinate to one inside 0..len, or None for a transparent pixel
+    fn resolve(self, i: i64, len: i64) -> Option<usize> {
+        if i >= 0 && i < len {
+            return Some(i as usize);
+        }
+        let i = match self {
+            EdgeMode::Clamp => i.clamp(0, len - 1),
+            EdgeMode::Wrap => i.rem_euclid(len),
+            EdgeMode::Mirror => {
+                if len == 1 {
+                    0
+                } else {
+                    let period = 2 * (len - 1);
+                    let i = i.rem_euclid(period);
+                    if i < len { i } else { period - i }
+                }
+            },
+            EdgeMode::Transparent => return None,
+        };
+        Some(i as usize)
+    }
+}
+
+/// A matrix of weights applied around each pixel by `Image::convolve`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Kernel {
+    w: u32,
+    h: u32,
+    weights: Vec<f32>,
+    bias: f32,
+}
+
+impl Kernel {
+    /// Create a kernel from weights in rows from top to bottom. Width and height must be odd,
+    /// so that the kernel is centered on a pixel
+    pub fn new(width: u32, height: u32, weights: Vec<f32>) -> Result<Self> {
+        if width.is_multiple_of(2) || height.is_multiple_of(2) || weights.len() as u64 != width as u64 * height as u64 {
+            return Err(Error::InvalidKernel {
+                width,
+                height,
+                len: weights.len(),
+            });
+        }
+
+        Ok(Kernel {
+            w: width,
+            h: height,
+            weights,
+            bias: 0.0,
+        })
+    }
+
+    /// Create a square kernel from an array of rows
+    fn square<const N: usize>(rows: [[f32; N]; N]) -> Self {
+        Kernel {
+            w: N as u32,
+            h: N as u32,
+            weights: rows.iter().flat_map(|row| row.iter().cloned()).collect(),
+            bias: 0.0,
+        }
+    }
+
+    /// Add a constant to every filtered channel, between 0 and 1. Useful to show
+    /// negative responses of kernels whose weights add up to zero
+    pub fn with_bias(mut self, bias: f32) -> Self {
+        self.bias = bias;
+        self
+    }
+
+    /// Sharpen edges by subtracting the neighbours
+    pub fn sharpen() -> Self {
+        Self::square([
+            [0.0, -1.0, 0.0],
+            [-1.0, 5.0, -1.0],
+            [0.0, -1.0, 0.0],
+        ])
+    }
+
+    /// Give the appearance of a relief lit from the top left
+    pub fn emboss() -> Self {
+        Self::square([
+            [-2.0, -1.0, 0.0],
+            [-1.0, 1.0, 1.0],
+            [0.0, 1.0, 2.0],
+        ])
+    }
+
+    /// Horizontal gradient, responding to vertical edges
+    pub fn sobel_x() -> Self {
+        Self::square([
+            [-1.0, 0.0, 1.0],
+            [-2.0, 0.0, 2.0],
+            [-1.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Vertical gradient, responding to horizontal edges
+    pub fn sobel_y() -> Self {
+        Self::square([
+            [-1.0, -2.0, -1.0],
+            [0.0, 0.0, 0.0],
+            [1.0, 2.0, 1.0],
+        ])
+    }
+
+    /// Second derivative, responding to edges in every direction
+    pub fn laplacian() -> Self {
+        Self::square([
+            [0.0, 1.0, 0.0],
+            [1.0, -4.0, 1.0],
+            [0.0, 1.0, 0.0],
+        ])
+    }
+
+    /// Get the width of the kernel
+    pub fn width(&self) -> u32 {
+        self.w
+    }
+
+    /// Get the height of the kernel
+    pub fn height(&self) -> u32 {
+        self.h
+    }
+
+    /// Get the weights in rows from top to bottom
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+}
+
+impl Image {
+    /// Get a copy of the image with a kernel applied to the red, green and blue channels.
+    /// Alpha is left unchanged
+    pub fn convolve(&self, kernel: &Kernel, edge: EdgeMode) -> Image {
+        if let Some(straight) = self.straight_copy() {
+            let mut image = straight.convolve(kernel, edge);
+            image.premultiply();
+            return image;
+        }
+
+        let mut data = Vec::with_capacity(self.data.len());
+        self.filter(kernel, edge, |sum, color| {
+            let alpha = color.data & 0xFF000000;
+            data.push(Color { data: alpha | to_rgb(sum, kernel.bias) });
+        });
+        self.with_data(self.w, self.h, data.into_boxed_slice())
+    }
+
+    /// Get a grayscale image of the gradient magnitude, as found by the Sobel operator.
+    /// Alpha is left unchanged
+    pub fn sobel(&self, edge: EdgeMode) -> Image {
+        if let Some(straight) = self.straight_copy() {
+            let mut image = straight.sobel(edge);
+            image.premultiply();
+            return image;
+        }
+
+        let gx = self.filtered(&Kernel::sobel_x(), edge);
+        let gy = self.filtered(&Kernel::sobel_y(), edge);
+        let data = gx.iter().zip(&gy).zip(self.data.iter()).map(|((x, y), color)| {
+            // Gradient of the luma
+            let luma = |c: &[f32; 3]| 0.299 * c[2] + 0.587 * c[1] + 0.114 * c[0];
+            let value = (luma(x).hypot(luma(y)) * 255.0 + 0.5).clamp(0.0, 255.0) as u32;
+            Color { data: (color.data & 0xFF000000) | value << 16 | value << 8 | value }
+        }).collect::<Vec<_>>();
+        self.with_data(self.w, self.h, data.into_boxed_slice())
+    }
+
+    /// Channel sums of a kernel applied at every pixel, as B, G, R between 0 and 1
+    fn filtered(&self, kernel: &Kernel, edge: EdgeMode) -> Vec<[f32; 3]> {
+        let mut sums = Vec::with_capacity(self.data.len());
+        self.filter(kernel, edge, |sum, _| sums.push(sum));
+        sums
+    }
+
+    /// Call `f` with the kernel sum and original color of each pixel, in order
+    fn filter<F: FnMut([f32; 3], Color)>(&self, kernel: &Kernel, edge: EdgeMode, mut f: F) {
+        let (w, h) = (self.w as i64, self.h as i64);
+        let (rx, ry) = (kernel.w as i64 / 2, kernel.h as i64 / 2);
+        for y in 0..h {
+            for x in 0..w {
+                let mut sum = [0.0; 3];
+                for ky in 0..kernel.h as i64 {
+                    let sy = match edge.resolve(y + ky - ry, h) {
+                        Some(sy) => sy,
+                        None => continue,
+                    };
+                    for kx in 0..kernel.w as i64 {
+                        let sx = match edge.resolve(x + kx - rx, w) {
+                            Some(sx) => sx,
+                            None => continue,
+                        };
+                        let weight = kernel.weights[(ky * kernel.w as i64 + kx) as usize];
+                        let [b, g, r, _] = self.data[sy * w as usize + sx].data.to_le_bytes();
+                        sum[0] += b as f32 * weight;
+                        sum[1] += g as f32 * weight;
+                        sum[2] += r as f32 * weight;
+                    }
+                }
+                f([sum[0] / 255.0, sum[1] / 255.0, sum[2] / 255.0], self.data[(y * w + x) as usize]);
+            }
+        }
+    }
+}
+
+/// Pack B, G, R sums into the low three bytes of a color
+fn to_rgb(sum: [f32; 3], bias: f32) -> u32 {
+    let byte = |c: f32| ((c + bias) * 255.0 + 0.5).clamp(0.0, 255.0) as u32;
+    byte(sum[2]) << 16 | byte(sum[1]) << 8 | byte(sum[0])
+}
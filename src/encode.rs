@@ -0,0 +1,81 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use image::{self, ColorType};
+use orbclient::Color;
+
+use alpha;
+use {AlphaMode, Error, Image, ImageFormat, Result};
+
+impl Image {
+    /// Save the image to a file path. The format is chosen from the file extension,
+    /// supporting PNG, BMP and JPEG. JPEG files are saved with a quality of 90
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let format = path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(ImageFormat::from_extension);
+
+        let encode: fn(&Image, &mut BufWriter<File>) -> Result<()> = match format {
+            Some(ImageFormat::Png) => |image, writer| image.encode_png(writer),
+            Some(ImageFormat::Bmp) => |image, writer| image.encode_bmp(writer),
+            Some(ImageFormat::Jpeg) => |image, writer| image.encode_jpeg(writer, 90),
+            _ => return Err(Error::UnsupportedFormat(format!("cannot encode {}", path.display()))),
+        };
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        encode(self, &mut writer)?;
+        writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Encode the image as PNG, including the alpha channel
+    pub fn encode_png<W: Write>(&self, writer: W) -> Result<()> {
+        image::png::PNGEncoder::new(writer).encode(&self.rgba_bytes(), self.w, self.h, ColorType::RGBA(8))?;
+        Ok(())
+    }
+
+    /// Encode the image as BMP, including the alpha channel
+    pub fn encode_bmp<W: Write>(&self, mut writer: W) -> Result<()> {
+        image::bmp::BMPEncoder::new(&mut writer).encode(&self.rgba_bytes(), self.w, self.h, ColorType::RGBA(8))?;
+        Ok(())
+    }
+
+    /// Encode the image as JPEG with a quality between 1 and 100. The alpha channel is discarded
+    pub fn encode_jpeg<W: Write>(&self, mut writer: W, quality: u8) -> Result<()> {
+        if self.w > u16::MAX as u32 || self.h > u16::MAX as u32 {
+            return Err(Error::UnsupportedFormat("JPEG dimensions exceed 65535".to_string()));
+        }
+
+        let quality = quality.clamp(1, 100);
+        image::jpeg::JPEGEncoder::new_with_quality(&mut writer, quality)
+            .encode(&self.rgb_bytes(), self.w, self.h, ColorType::RGB(8))?;
+        Ok(())
+    }
+
+    /// Pixel colors with straight alpha, as files store them
+    fn straight_colors<'a>(&'a self) -> impl Iterator<Item = Color> + 'a {
+        let premultiplied = self.alpha == AlphaMode::Premultiplied;
+        self.data.iter().map(move |&color| if premultiplied { alpha::unpremultiply(color) } else { color })
+    }
+
+    /// Pixel data as tightly packed RGBA bytes
+    fn rgba_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.data.len() * 4);
+        for color in self.straight_colors() {
+            bytes.extend_from_slice(&[color.r(), color.g(), color.b(), color.a()]);
+        }
+        bytes
+    }
+
+    /// Pixel data as tightly packed RGB bytes
+    fn rgb_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.data.len() * 3);
+        for color in self.straight_colors() {
+            bytes.extend_from_slice(&[color.r(), color.g(), color.b()]);
+        }
+        bytes
+    }
+}
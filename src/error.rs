@@ -0,0 +1,84 @@
+use std::{error, fmt, io, result};
+
+use image::ImageError;
+
+/// Errors that can occur while loading, converting or processing images
+#[derive(Debug)]
+pub enum Error {
+    /// An I/O error occurred while reading or writing image data
+    Io(io::Error),
+    /// The image format is not recognized or not supported
+    UnsupportedFormat(String),
+    /// The image data could not be decoded
+    Decode(ImageError),
+    /// The amount of pixel data does not match the given width and height
+    DimensionMismatch {
+        width: u32,
+        height: u32,
+        len: usize,
+    },
+    /// The given width and height do not fit in memory
+    SizeOverflow {
+        width: u32,
+        height: u32,
+    },
+    /// The image could not be resized
+    Resize(String),
+    /// A convolution kernel has an even width or height, or the wrong number of weights
+    InvalidKernel {
+        width: u32,
+        height: u32,
+        len: usize,
+    },
+}
+
+/// Result type used throughout the crate
+pub type Result<T> = result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref err) => write!(f, "I/O error: {}", err),
+            Error::UnsupportedFormat(ref format) => write!(f, "unsupported image format: {}", format),
+            Error::Decode(ref err) => write!(f, "failed to decode image: {}", err),
+            Error::DimensionMismatch { width, height, len } => write!(
+                f,
+                "{} pixels given for a {}x{} image",
+                len, width, height
+            ),
+            Error::SizeOverflow { width, height } => write!(f, "image size {}x{} is too large", width, height),
+            Error::Resize(ref reason) => write!(f, "failed to resize image: {}", reason),
+            Error::InvalidKernel { width, height, len } => write!(
+                f,
+                "invalid {}x{} kernel with {} weights, sizes must be odd",
+                width, height, len
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::Decode(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<ImageError> for Error {
+    fn from(err: ImageError) -> Self {
+        match err {
+            ImageError::IoError(err) => Error::Io(err),
+            ImageError::UnsupportedError(format) => Error::UnsupportedFormat(format),
+            err => Error::Decode(err),
+        }
+    }
+}
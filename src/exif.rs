@@ -0,0 +1,164 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+use Image;
+
+/// Orientation of the stored pixels, as given by the EXIF Orientation tag
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    /// Stored upright
+    Normal,
+    /// Stored mirrored left to right
+    FlipHorizontal,
+    /// Stored upside down
+    Rotate180,
+    /// Stored mirrored top to bottom
+    FlipVertical,
+    /// Stored mirrored along the main diagonal
+    Transpose,
+    /// Stored rotated, displayed by rotating 90 degrees clockwise
+    Rotate90,
+    /// Stored mirrored along the anti-diagonal
+    Transverse,
+    /// Stored rotated, displayed by rotating 270 degrees clockwise
+    Rotate270,
+}
+
+impl Orientation {
+    /// Convert a raw EXIF Orientation value, between 1 and 8
+    pub fn from_exif(value: u16) -> Option<Self> {
+        let orientation = match value {
+            1 => Orientation::Normal,
+            2 => Orientation::FlipHorizontal,
+            3 => Orientation::Rotate180,
+            4 => Orientation::FlipVertical,
+            5 => Orientation::Transpose,
+            6 => Orientation::Rotate90,
+            7 => Orientation::Transverse,
+            8 => Orientation::Rotate270,
+            _ => return None,
+        };
+        Some(orientation)
+    }
+
+    /// The raw EXIF Orientation value
+    pub fn to_exif(self) -> u16 {
+        match self {
+            Orientation::Normal => 1,
+            Orientation::FlipHorizontal => 2,
+            Orientation::Rotate180 => 3,
+            Orientation::FlipVertical => 4,
+            Orientation::Transpose => 5,
+            Orientation::Rotate90 => 6,
+            Orientation::Transverse => 7,
+            Orientation::Rotate270 => 8,
+        }
+    }
+}
+
+impl Image {
+    /// Transform an image stored with the given orientation so that it displays upright
+    pub fn apply_orientation(&mut self, orientation: Orientation) {
+        match orientation {
+            Orientation::Normal => (),
+            Orientation::FlipHorizontal => self.flip_horizontal_in_place(),
+            Orientation::Rotate180 => self.rotate180_in_place(),
+            Orientation::FlipVertical => self.flip_vertical_in_place(),
+            Orientation::Transpose => self.transpose_in_place(),
+            Orientation::Rotate90 => self.rotate90_in_place(),
+            Orientation::Transverse => {
+                self.rotate270_in_place();
+                self.flip_horizontal_in_place();
+            },
+            Orientation::Rotate270 => self.rotate270_in_place(),
+        }
+    }
+}
+
+/// Read the raw EXIF Orientation value of JPEG data, if it has one
+pub fn exif_orientation(data: &[u8]) -> Option<u16> {
+    read_exif_orientation(&mut io::Cursor::new(data)).unwrap_or(None)
+}
+
+/// Read the raw EXIF Orientation value from the JPEG headers of a reader,
+/// leaving the reader where it was
+pub(crate) fn read_exif_orientation<R: Read + Seek>(reader: &mut R) -> io::Result<Option<u16>> {
+    let start = reader.stream_position()?;
+    // Malformed headers are left for the decoder to report
+    let orientation = find_exif(reader).ok()
+        .and_then(|exif| exif)
+        .and_then(|exif| tiff_orientation(&exif));
+    reader.seek(SeekFrom::Start(start))?;
+    Ok(orientation)
+}
+
+/// Walk the JPEG markers up to the start of scan, returning the contents of the EXIF segment
+fn find_exif<R: Read>(reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+    let mut byte = [0; 1];
+    let mut soi = [0; 2];
+    reader.read_exact(&mut soi)?;
+    if soi != [0xFF, 0xD8] {
+        return Ok(None);
+    }
+
+    loop {
+        reader.read_exact(&mut byte)?;
+        if byte[0] != 0xFF {
+            return Ok(None);
+        }
+        // Markers may be preceded by any number of fill bytes
+        while byte[0] == 0xFF {
+            reader.read_exact(&mut byte)?;
+        }
+
+        let marker = byte[0];
+        match marker {
+            // Markers without a length
+            0x01 | 0xD0..=0xD8 => continue,
+            // Start of scan or end of image, no EXIF segment after this
+            0xD9 | 0xDA => return Ok(None),
+            _ => (),
+        }
+
+        let mut len = [0; 2];
+        reader.read_exact(&mut len)?;
+        let len = (u16::from_be_bytes(len) as u64).saturating_sub(2);
+        let mut segment = Vec::with_capacity(len as usize);
+        reader.by_ref().take(len).read_to_end(&mut segment)?;
+        if marker == 0xE1 && segment.starts_with(b"Exif\0\0") {
+            return Ok(Some(segment.split_off(6)));
+        }
+    }
+}
+
+/// Find the Orientation tag in the first IFD of TIFF structured data
+fn tiff_orientation(tiff: &[u8]) -> Option<u16> {
+    let big_endian = match tiff.get(0..2)? {
+        b"II" => false,
+        b"MM" => true,
+        _ => return None,
+    };
+    let u16_at = |offset: usize| -> Option<u16> {
+        let bytes = [*tiff.get(offset)?, *tiff.get(offset + 1)?];
+        Some(if big_endian { u16::from_be_bytes(bytes) } else { u16::from_le_bytes(bytes) })
+    };
+    let u32_at = |offset: usize| -> Option<u32> {
+        let bytes = [*tiff.get(offset)?, *tiff.get(offset + 1)?, *tiff.get(offset + 2)?, *tiff.get(offset + 3)?];
+        Some(if big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) })
+    };
+
+    if u16_at(2)? != 42 {
+        return None;
+    }
+
+    let ifd = u32_at(4)? as usize;
+    let count = u16_at(ifd)? as usize;
+    for i in 0..count {
+        let entry = ifd + 2 + i * 12;
+        // Orientation is stored as a single SHORT
+        if u16_at(entry)? == 0x0112 && u16_at(entry + 2)? == 3 {
+            return u16_at(entry + 8);
+        }
+    }
+
+    None
+}
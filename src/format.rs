@@ -0,0 +1,80 @@
+use image;
+
+/// Image file formats that can be detected and decoded
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Bmp,
+    Ico,
+    Tiff,
+    Webp,
+    Pnm,
+    Hdr,
+    /// TGA files have no magic bytes and are only recognized by extension
+    Tga,
+}
+
+impl ImageFormat {
+    /// Detect the format of encoded image data from its leading magic bytes
+    pub fn from_magic(data: &[u8]) -> Option<Self> {
+        let format = if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            ImageFormat::Png
+        } else if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            ImageFormat::Jpeg
+        } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+            ImageFormat::Gif
+        } else if data.starts_with(b"BM") {
+            ImageFormat::Bmp
+        } else if data.starts_with(&[0, 0, 1, 0]) {
+            ImageFormat::Ico
+        } else if data.starts_with(b"II*\0") || data.starts_with(b"MM\0*") {
+            ImageFormat::Tiff
+        } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WEBP") {
+            ImageFormat::Webp
+        } else if data.len() >= 2 && data[0] == b'P' && (b'1'..=b'7').contains(&data[1]) {
+            ImageFormat::Pnm
+        } else if data.starts_with(b"#?RADIANCE") || data.starts_with(b"#?RGBE") {
+            ImageFormat::Hdr
+        } else {
+            return None;
+        };
+
+        Some(format)
+    }
+
+    /// Guess the format from a file extension, without the leading dot
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        let format = match extension.to_lowercase().as_str() {
+            "png" | "apng" => ImageFormat::Png,
+            "jpg" | "jpeg" | "jpe" | "jfif" => ImageFormat::Jpeg,
+            "gif" => ImageFormat::Gif,
+            "bmp" | "dib" => ImageFormat::Bmp,
+            "ico" => ImageFormat::Ico,
+            "tif" | "tiff" => ImageFormat::Tiff,
+            "webp" => ImageFormat::Webp,
+            "pbm" | "pgm" | "ppm" | "pam" | "pnm" => ImageFormat::Pnm,
+            "hdr" => ImageFormat::Hdr,
+            "tga" => ImageFormat::Tga,
+            _ => return None,
+        };
+
+        Some(format)
+    }
+
+    pub(crate) fn to_image_format(self) -> image::ImageFormat {
+        match self {
+            ImageFormat::Png => image::ImageFormat::PNG,
+            ImageFormat::Jpeg => image::ImageFormat::JPEG,
+            ImageFormat::Gif => image::ImageFormat::GIF,
+            ImageFormat::Bmp => image::ImageFormat::BMP,
+            ImageFormat::Ico => image::ImageFormat::ICO,
+            ImageFormat::Tiff => image::ImageFormat::TIFF,
+            ImageFormat::Webp => image::ImageFormat::WEBP,
+            ImageFormat::Pnm => image::ImageFormat::PNM,
+            ImageFormat::Hdr => image::ImageFormat::HDR,
+            ImageFormat::Tga => image::ImageFormat::TGA,
+        }
+    }
+}
@@ -4,35 +4,69 @@
 extern crate orbclient;
 extern crate resize;
 extern crate image;
+extern crate gif;
 
-use std::{cmp, slice};
+use std::mem;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
 use std::path::Path;
-use std::error::Error;
 use std::cell::Cell;
 
 use orbclient::{Color, Renderer, Mode};
 
+pub use adjust::ColorMatrix;
+pub use affine::{Affine, Interpolation};
+pub use alpha::AlphaMode;
+pub use animation::{Animation, BlendOp, DisposeOp, Frame, LoopCount};
+pub use composite::CompositeOp;
+pub use convolve::{EdgeMode, Kernel};
+pub use error::{Error, Result};
+pub use exif::{exif_orientation, Orientation};
+pub use format::ImageFormat;
+pub use nine_patch::{Insets, NinePatch, PatchMode};
+pub use perspective::Homography;
+pub use probe::{probe, probe_bytes, probe_reader, ImageInfo};
 pub use resize::Type as ResizeType;
-
-pub struct ImageRoi<'a> {
-    x: u32,
-    y: u32,
-    w: u32,
-    h: u32,
-    image: &'a Image
+pub use resizer::Resizer;
+pub use roi::{ImageRoi, ImageRoiMut};
+pub use scale::{Placement, ScaleMode};
+pub use tint::TintCache;
+
+mod adjust;
+mod affine;
+mod alpha;
+mod animation;
+mod blur;
+mod composite;
+mod convolve;
+mod encode;
+mod error;
+mod exif;
+mod format;
+mod linear;
+mod nine_patch;
+mod pixel_art;
+mod perspective;
+mod probe;
+mod resizer;
+mod roi;
+mod scale;
+mod shadow;
+mod tint;
+mod transform;
+mod trim;
+
+/// Options controlling how encoded images are loaded
+#[derive(Clone, Debug)]
+pub struct LoadOptions {
+    /// Rotate and flip JPEG photos according to their EXIF Orientation tag
+    pub apply_orientation: bool,
 }
 
-impl<'a> ImageRoi<'a> {
-    /// Draw the ROI on a window
-    pub fn draw<R: Renderer>(&self, renderer: &mut R, x: i32, mut y: i32) {
-        let stride = self.image.w;
-        let mut offset = (self.y * stride + self.x) as usize;
-        let last_offset = cmp::min(((self.y + self.h) * stride + self.x) as usize, self.image.data.len());
-        while offset < last_offset {
-            let next_offset = offset + stride as usize;
-            renderer.image(x, y, self.w, 1, &self.image.data[offset..]);
-            offset = next_offset;
-            y += 1;
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            apply_orientation: true,
         }
     }
 }
@@ -43,6 +77,8 @@ pub struct Image {
     h: u32,
     // Drawing mode
     mode: Cell<Mode>,
+    // Whether colors are premultiplied by alpha
+    alpha: AlphaMode,
     data: Box<[Color]>
 }
 
@@ -58,21 +94,27 @@ impl Image {
     }
 
     /// Create a new image from a boxed slice of colors
-    pub fn from_data(width: u32, height: u32, data: Box<[Color]>) -> Result<Self, String> {
-        if (width * height) as usize != data.len() {
-            return Err("not enough or too much data given compared to width and height".to_string())
+    pub fn from_data(width: u32, height: u32, data: Box<[Color]>) -> Result<Self> {
+        let len = pixel_count(width, height)?;
+        if len != data.len() {
+            return Err(Error::DimensionMismatch {
+                width,
+                height,
+                len: data.len(),
+            });
         }
 
         Ok(Image {
             w: width,
             h: height,
             mode: Cell::new(Mode::Blend),
-            data: data,
+            alpha: AlphaMode::Straight,
+            data,
         })
     }
 
-    fn from_dynamic_image(d_img: image::ImageResult<image::DynamicImage>) -> Result<Self, String> {
-        let img = d_img.map_err(|e| e.description().to_string())?.to_rgba();
+    fn from_dynamic_image(d_img: image::ImageResult<image::DynamicImage>) -> Result<Self> {
+        let img = d_img?.to_rgba();
         let data: Vec<_> = img.pixels().map(
             |p| Color::rgba(p.data[0], p.data[1], p.data[2], p.data[3])
             ).collect();
@@ -80,51 +122,101 @@ impl Image {
 
     }
 
-    /// Load an image from file path. Supports BMP and PNG
-    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, String> {
-        let img = image::open(path);
-        Self::from_dynamic_image(img)
+    /// Load an image from file path. The format is detected from the file contents,
+    /// falling back to the file extension for formats without magic bytes
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_path_with_options(path, &LoadOptions::default())
     }
 
-    /// Create a new empty image
-    pub fn default() -> Self {
-        Self::new(0, 0)
+    /// Load an image from file path with the given options
+    pub fn from_path_with_options<P: AsRef<Path>>(path: P, options: &LoadOptions) -> Result<Self> {
+        let path = path.as_ref();
+        let mut reader = BufReader::new(File::open(path)?);
+        let format = path_format(&mut reader, path)?;
+        Self::from_buf_reader(reader, format, options)
+    }
+
+    /// Load an image from any seekable reader, detecting the format from its magic bytes
+    pub fn from_reader<R: Read + Seek>(reader: R) -> Result<Self> {
+        Self::from_reader_with_options(reader, &LoadOptions::default())
     }
 
-    // Get a resized version of the image
-    pub fn resize(&self, w: u32, h: u32, resize_type: ResizeType) -> Result<Self, String> {
-        let mut dst_color = vec![Color { data: 0 }; w as usize * h as usize].into_boxed_slice();
+    /// Load an image from any seekable reader with the given options
+    pub fn from_reader_with_options<R: Read + Seek>(reader: R, options: &LoadOptions) -> Result<Self> {
+        let mut reader = BufReader::new(reader);
+        let format = sniff_format(&mut reader)?
+            .ok_or_else(|| Error::UnsupportedFormat("unrecognized magic bytes".to_string()))?;
+        Self::from_buf_reader(reader, format, options)
+    }
 
-        let src = unsafe {
-            slice::from_raw_parts(self.data.as_ptr() as *const u8, self.data.len() * 4)
-        };
+    /// Load an image of a known format from any seekable reader
+    pub fn from_reader_with_format<R: Read + Seek>(reader: R, format: ImageFormat) -> Result<Self> {
+        Self::from_buf_reader(BufReader::new(reader), format, &LoadOptions::default())
+    }
 
-        let mut dst = unsafe {
-            slice::from_raw_parts_mut(dst_color.as_mut_ptr() as *mut u8, dst_color.len() * 4)
+    fn from_buf_reader<R: BufRead + Seek>(mut reader: R, format: ImageFormat, options: &LoadOptions) -> Result<Self> {
+        let orientation = if format == ImageFormat::Jpeg && options.apply_orientation {
+            exif::read_exif_orientation(&mut reader)?.and_then(Orientation::from_exif)
+        } else {
+            None
         };
 
-        let mut resizer = resize::new(self.w as usize, self.h as usize,
-                                      w as usize, h as usize,
-                                      resize::Pixel::RGBA, resize_type);
-        resizer.resize(&src, &mut dst);
+        let img = image::load(reader, format.to_image_format());
+        let mut image = Self::from_dynamic_image(img)?;
+        if let Some(orientation) = orientation {
+            image.apply_orientation(orientation);
+        }
+        Ok(image)
+    }
 
-        Image::from_data(w, h, dst_color)
+    /// Load an image from encoded bytes in memory, detecting the format from its magic bytes
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        Self::from_reader(Cursor::new(data))
+    }
+
+    /// Load an image from encoded bytes in memory with the given options
+    pub fn from_bytes_with_options(data: &[u8], options: &LoadOptions) -> Result<Self> {
+        Self::from_reader_with_options(Cursor::new(data), options)
+    }
+
+    /// Get a resized version of the image. Use a `Resizer` to resize to the same size repeatedly
+    pub fn resize(&self, w: u32, h: u32, resize_type: ResizeType) -> Result<Self> {
+        Resizer::new(self.w, self.h, w, h, resize_type)?.resize(self)
     }
 
     /// Get a piece of the image
     pub fn roi<'a>(&'a self, x: u32, y: u32, w: u32, h: u32) -> ImageRoi<'a> {
-        let x1 = cmp::min(x, self.width());
-        let y1 = cmp::min(y, self.height());
-        let x2 = cmp::max(x1, cmp::min(x + w, self.width()));
-        let y2 = cmp::max(y1, cmp::min(y + h, self.height()));
-
-        ImageRoi {
-            x: x1,
-            y: y1,
-            w: x2 - x1,
-            h: y2 - y1,
-            image: self
+        ImageRoi::new(self, x, y, w, h)
+    }
+
+    /// Get a copy of a piece of the image. The rectangle is clamped to the image bounds
+    pub fn crop(&self, x: u32, y: u32, w: u32, h: u32) -> Self {
+        self.roi(x, y, w, h).to_image()
+    }
+
+    /// Shrink the image to a piece of itself. The rectangle is clamped to the image bounds
+    pub fn crop_in_place(&mut self, x: u32, y: u32, w: u32, h: u32) {
+        let (x, y, w, h) = {
+            let roi = self.roi(x, y, w, h);
+            (roi.x(), roi.y(), roi.width(), roi.height())
+        };
+
+        let stride = self.w as usize;
+        let mut data = mem::take(&mut self.data).into_vec();
+        for row in 0..h as usize {
+            let start = (y as usize + row) * stride + x as usize;
+            data.copy_within(start..start + w as usize, row * w as usize);
         }
+        data.truncate(w as usize * h as usize);
+
+        self.w = w;
+        self.h = h;
+        self.data = data.into_boxed_slice();
+    }
+
+    /// Get a mutable piece of the image that can be drawn into
+    pub fn roi_mut<'a>(&'a mut self, x: u32, y: u32, w: u32, h: u32) -> ImageRoiMut<'a> {
+        ImageRoiMut::new(self, x, y, w, h)
     }
 
     /// Return a boxed slice of colors making up the image
@@ -134,10 +226,20 @@ impl Image {
 
     /// Draw the image on a window
     pub fn draw<R: Renderer>(&self, renderer: &mut R, x: i32, y: i32) {
+        if let Some(straight) = self.straight_copy() {
+            return straight.draw(renderer, x, y);
+        }
         renderer.image(x, y, self.w, self.h, &self.data);
     }
 }
 
+impl Default for Image {
+    /// Create a new empty image
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
 impl Renderer for Image {
     /// Get the width of the image in pixels
     fn width(&self) -> u32 {
@@ -162,23 +264,55 @@ impl Renderer for Image {
     fn sync(&mut self) -> bool {
         true
     }
+
+    fn update(&mut self) -> bool {
+        true
+    }
+
+    fn update_rects(&mut self, _rects: &[(i32, i32, u32, u32)]) -> bool {
+        true
+    }
     
     fn mode(&self) -> &Cell<Mode> {
     &self.mode
     }
 }
 
-pub fn parse_png(data: &[u8]) -> Result<Image, String> {
+/// Peek at the start of a reader to detect the image format, leaving the reader where it was
+fn sniff_format<R: Read + Seek>(reader: &mut R) -> Result<Option<ImageFormat>> {
+    let start = reader.stream_position()?;
+    let mut magic = Vec::with_capacity(16);
+    reader.by_ref().take(16).read_to_end(&mut magic)?;
+    reader.seek(SeekFrom::Start(start))?;
+    Ok(ImageFormat::from_magic(&magic))
+}
+
+/// Detect the format of a file from its magic bytes, falling back to its extension
+fn path_format<R: Read + Seek>(reader: &mut R, path: &Path) -> Result<ImageFormat> {
+    match sniff_format(reader)? {
+        Some(format) => Ok(format),
+        None => path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(ImageFormat::from_extension)
+            .ok_or_else(|| Error::UnsupportedFormat(path.display().to_string())),
+    }
+}
+
+/// Number of pixels in an image of the given size, failing if it does not fit in memory
+fn pixel_count(width: u32, height: u32) -> Result<usize> {
+    (width as usize).checked_mul(height as usize).ok_or(Error::SizeOverflow { width, height })
+}
+
+pub fn parse_png(data: &[u8]) -> Result<Image> {
     let img = image::load_from_memory_with_format(data, image::ImageFormat::PNG);
     Image::from_dynamic_image(img)
 }
 
-pub fn parse_bmp(data: &[u8]) -> Result<Image, String> {
+pub fn parse_bmp(data: &[u8]) -> Result<Image> {
     let img = image::load_from_memory_with_format(data, image::ImageFormat::BMP);
     Image::from_dynamic_image(img)
 }
 
-pub fn parse_jpg(data: &[u8]) -> Result<Image, String> {
-    let img = image::load_from_memory_with_format(data, image::ImageFormat::JPEG);
-    Image::from_dynamic_image(img)
+pub fn parse_jpg(data: &[u8]) -> Result<Image> {
+    Image::from_reader_with_format(Cursor::new(data), ImageFormat::Jpeg)
 }
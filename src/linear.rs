@@ -0,0 +1,92 @@
+use std::sync::OnceLock;
+
+use orbclient::Color;
+
+use {alpha, AlphaMode, Image, ResizeType, Resizer, Result};
+
+/// Bits of linear light kept when looking up the sRGB encoding
+const LINEAR_BITS: u32 = 12;
+
+impl Image {
+    /// Get a resized version of the image, filtering in linear light instead of on the
+    /// sRGB encoded values. Downscaled photos and thin lines keep their brightness,
+    /// at the cost of converting every pixel on the way in and out
+    pub fn resize_linear(&self, w: u32, h: u32, resize_type: ResizeType) -> Result<Self> {
+        Resizer::new_linear(self.w, self.h, w, h, resize_type)?.resize(self)
+    }
+}
+
+/// Convert colors to 16 bit linear light B, G, R, A channels, premultiplied by alpha
+pub(crate) fn to_linear(data: &[Color], mode: AlphaMode, linear: &mut Vec<u16>) {
+    let table = decode_table();
+    linear.clear();
+    linear.reserve(data.len() * 4);
+    for &color in data {
+        let color = match mode {
+            AlphaMode::Straight => color,
+            AlphaMode::Premultiplied => alpha::unpremultiply(color),
+        };
+        let [b, g, r, a] = color.data.to_le_bytes();
+        let premultiply = |c: u8| ((table[c as usize] as u32 * a as u32 + 127) / 255) as u16;
+        linear.extend_from_slice(&[premultiply(b), premultiply(g), premultiply(r), a as u16 * 257]);
+    }
+}
+
+/// Convert premultiplied 16 bit linear light channels back to colors
+pub(crate) fn from_linear(linear: &[u16], data: &mut [Color], mode: AlphaMode) {
+    let table = encode_table();
+    for (color, channels) in data.iter_mut().zip(linear.chunks(4)) {
+        let a = channels[3] as u32;
+        let encode = |c: u16| {
+            let c = (c as u32 * 65535 + a / 2) / a;
+            table[(c.min(65535) >> (16 - LINEAR_BITS)) as usize]
+        };
+        *color = if a == 0 {
+            Color { data: 0 }
+        } else {
+            Color { data: u32::from_le_bytes([encode(channels[0]), encode(channels[1]), encode(channels[2]), ((a + 128) / 257) as u8]) }
+        };
+        if mode == AlphaMode::Premultiplied {
+            *color = alpha::premultiply(*color);
+        }
+    }
+}
+
+/// sRGB encoded bytes to 16 bit linear light
+fn decode_table() -> &'static [u16; 256] {
+    static TABLE: OnceLock<[u16; 256]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = [0; 256];
+        for (i, value) in table.iter_mut().enumerate() {
+            *value = (srgb_to_linear(i as f32 / 255.0) * 65535.0 + 0.5) as u16;
+        }
+        table
+    })
+}
+
+/// Linear light, reduced to `LINEAR_BITS`, to sRGB encoded bytes
+fn encode_table() -> &'static [u8] {
+    static TABLE: OnceLock<Vec<u8>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let max = ((1 << LINEAR_BITS) - 1) as f32;
+        (0..1 << LINEAR_BITS)
+            .map(|i| (linear_to_srgb(i as f32 / max) * 255.0 + 0.5) as u8)
+            .collect()
+    })
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
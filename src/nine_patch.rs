@@ -0,0 +1,189 @@
+use std::cmp;
+use std::path::Path;
+
+use image::ImageError;
+use orbclient::{Color, Mode, Renderer};
+
+use {Error, Image, ResizeType, Result};
+
+/// Widths of the borders around the middle of an image
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Insets {
+    pub left: u32,
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+}
+
+/// How the edges and middle of a nine-patch fill their space
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatchMode {
+    /// Resize the piece to the space
+    Stretch,
+    /// Repeat the piece from its top left corner
+    Tile,
+}
+
+/// An image split into a 3x3 grid by insets. Corners are drawn as they are, edges are
+/// stretched or tiled along one axis and the middle along both, so the image can cover
+/// any size without distorting its corners
+#[derive(Clone)]
+pub struct NinePatch {
+    image: Image,
+    insets: Insets,
+    padding: Option<Insets>,
+    edges: PatchMode,
+    center: PatchMode,
+}
+
+impl NinePatch {
+    /// Split an image with the given insets, which are reduced if they do not fit the image
+    pub fn new(image: Image, insets: Insets) -> Self {
+        let left = cmp::min(insets.left, image.w);
+        let top = cmp::min(insets.top, image.h);
+        let insets = Insets {
+            left,
+            top,
+            right: cmp::min(insets.right, image.w - left),
+            bottom: cmp::min(insets.bottom, image.h - top),
+        };
+
+        NinePatch {
+            image,
+            insets,
+            padding: None,
+            edges: PatchMode::Stretch,
+            center: PatchMode::Stretch,
+        }
+    }
+
+    /// Load an Android style `.9.png` file, see `NinePatch::from_guides`
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_guides(Image::from_path(path)?)
+    }
+
+    /// Split an image with a one pixel guide border, as used by Android `.9.png` files.
+    /// Black pixels on the top and left borders mark the stretched middle, and black pixels
+    /// on the bottom and right borders mark the padding around content. Only the span from
+    /// the first to the last guide pixel is used, the border itself is removed
+    pub fn from_guides(image: Image) -> Result<Self> {
+        if image.w < 3 || image.h < 3 {
+            return Err(guide_error("image too small for nine-patch guides"));
+        }
+
+        let (w, h) = (image.w as usize, image.h as usize);
+        let guide = |x: usize, y: usize| image.data[y * w + x].data == 0xFF000000;
+        let span = |len: usize, at: &dyn Fn(usize) -> bool| -> Option<(u32, u32)> {
+            let first = (1..len - 1).find(|&i| at(i))?;
+            let last = (1..len - 1).rev().find(|&i| at(i))?;
+            // Insets before and after the span, inside the border
+            Some(((first - 1) as u32, (len - 2 - last) as u32))
+        };
+
+        let (left, right) = span(w, &|x| guide(x, 0)).ok_or_else(|| guide_error("top nine-patch guide missing"))?;
+        let (top, bottom) = span(h, &|y| guide(0, y)).ok_or_else(|| guide_error("left nine-patch guide missing"))?;
+        let padding = match (span(w, &|x| guide(x, h - 1)), span(h, &|y| guide(w - 1, y))) {
+            (Some((left, right)), Some((top, bottom))) => Some(Insets { left, top, right, bottom }),
+            _ => None,
+        };
+
+        let mut patch = NinePatch::new(image.crop(1, 1, image.w - 2, image.h - 2), Insets { left, top, right, bottom });
+        patch.padding = padding;
+        Ok(patch)
+    }
+
+    /// Set how the edges fill their space
+    pub fn edges(mut self, mode: PatchMode) -> Self {
+        self.edges = mode;
+        self
+    }
+
+    /// Set how the middle fills its space
+    pub fn center(mut self, mode: PatchMode) -> Self {
+        self.center = mode;
+        self
+    }
+
+    /// Get the image that is split
+    pub fn image(&self) -> &Image {
+        &self.image
+    }
+
+    /// Get the widths of the corners and edges
+    pub fn insets(&self) -> Insets {
+        self.insets
+    }
+
+    /// Get the space to leave around content drawn on top, if the guides gave one
+    pub fn padding(&self) -> Option<Insets> {
+        self.padding
+    }
+
+    /// Draw the nine-patch on a window, covering a rectangle
+    pub fn draw<R: Renderer>(&self, renderer: &mut R, x: i32, y: i32, w: u32, h: u32) {
+        let insets = self.insets;
+        let src_cols = split(insets.left, insets.right, self.image.w);
+        let src_rows = split(insets.top, insets.bottom, self.image.h);
+        let dst_cols = split(insets.left, insets.right, w);
+        let dst_rows = split(insets.top, insets.bottom, h);
+
+        for row in 0..3 {
+            for col in 0..3 {
+                let (sx, sw) = src_cols[col];
+                let (sy, sh) = src_rows[row];
+                let (dx, dw) = dst_cols[col];
+                let (dy, dh) = dst_rows[row];
+                if sw == 0 || sh == 0 || dw == 0 || dh == 0 {
+                    continue;
+                }
+
+                let (dx, dy) = (x + dx as i32, y + dy as i32);
+                if (sw, sh) == (dw, dh) {
+                    self.image.roi(sx, sy, sw, sh).draw(renderer, dx, dy);
+                    continue;
+                }
+
+                let mode = match (row, col) {
+                    (1, 1) => self.center,
+                    _ => self.edges,
+                };
+                let piece = self.image.crop(sx, sy, sw, sh);
+                let filled = match mode {
+                    PatchMode::Stretch => match piece.resize(dw, dh, ResizeType::Triangle) {
+                        Ok(resized) => resized,
+                        Err(_) => continue,
+                    },
+                    PatchMode::Tile => piece.tile(dw, dh),
+                };
+                filled.draw(renderer, dx, dy);
+            }
+        }
+    }
+
+    /// Get an image of the nine-patch covering `w` by `h` pixels
+    pub fn render(&self, w: u32, h: u32) -> Image {
+        let mut image = Image::from_color(w, h, Color::rgba(0, 0, 0, 0));
+        // Pieces do not overlap, so copy them instead of blending with the transparent background
+        image.mode().set(Mode::Overwrite);
+        self.draw(&mut image, 0, 0, w, h);
+        image.mode().set(self.image.mode.get());
+        image
+    }
+}
+
+/// Offsets and lengths of the three pieces along one axis. If the space is smaller than
+/// the two insets, they shrink in proportion and the middle disappears
+fn split(before: u32, after: u32, space: u32) -> [(u32, u32); 3] {
+    let (before, after) = if before as u64 + after as u64 > space as u64 {
+        let before = (space as u64 * before as u64 / (before as u64 + after as u64)) as u32;
+        (before, space - before)
+    } else {
+        (before, after)
+    };
+    let middle = space - before - after;
+    [(0, before), (before, middle), (before + middle, after)]
+}
+
+fn guide_error(message: &str) -> Error {
+    Error::Decode(ImageError::FormatError(message.to_string()))
+}
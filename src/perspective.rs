@@ -0,0 +1,159 @@
+use orbclient::Color;
+
+use {Image, Interpolation};
+
+/// A projective transform, mapping `(x, y)` to `((a x + b y + c) / w, (d x + e y + f) / w)`
+/// where `w = g x + h y + 1`. Unlike `Affine`, it can map a rectangle to any quadrilateral
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Homography {
+    m: [f64; 9],
+}
+
+impl Homography {
+    /// Create a homography from a row-major 3x3 matrix
+    pub fn new(m: [f64; 9]) -> Self {
+        Homography { m }
+    }
+
+    /// Solve for the homography mapping each of four points to the matching destination point.
+    /// Returns None if three of the points lie on one line
+    pub fn from_points(src: [(f64, f64); 4], dst: [(f64, f64); 4]) -> Option<Self> {
+        // Two equations per point pair for the eight unknowns, with the last entry fixed at 1
+        let mut rows = [[0.0; 9]; 8];
+        for (i, (&(x, y), &(u, v))) in src.iter().zip(dst.iter()).enumerate() {
+            rows[2 * i] = [x, y, 1.0, 0.0, 0.0, 0.0, -u * x, -u * y, u];
+            rows[2 * i + 1] = [0.0, 0.0, 0.0, x, y, 1.0, -v * x, -v * y, v];
+        }
+
+        // Gaussian elimination with partial pivoting
+        for col in 0..8 {
+            let pivot = (col..8).max_by(|&a, &b| rows[a][col].abs().total_cmp(&rows[b][col].abs()))?;
+            if rows[pivot][col].abs() < 1e-10 {
+                return None;
+            }
+            rows.swap(col, pivot);
+            let pivot_row = rows[col];
+            for (i, row) in rows.iter_mut().enumerate() {
+                if i != col {
+                    let factor = row[col] / pivot_row[col];
+                    for (value, pivot) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                        *value -= factor * pivot;
+                    }
+                }
+            }
+        }
+
+        let mut m = [1.0; 9];
+        for (i, row) in rows.iter().enumerate() {
+            m[i] = row[8] / row[i];
+        }
+        Some(Homography { m })
+    }
+
+    /// Get the row-major 3x3 matrix
+    pub fn matrix(&self) -> [f64; 9] {
+        self.m
+    }
+
+    /// Get the transform undoing this one, if it does not collapse the plane
+    pub fn invert(&self) -> Option<Homography> {
+        let [a, b, c, d, e, f, g, h, i] = self.m;
+        let det = a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g);
+        if det.abs() < 1e-12 || !det.is_finite() {
+            return None;
+        }
+
+        let adjugate = [
+            e * i - f * h, c * h - b * i, b * f - c * e,
+            f * g - d * i, a * i - c * g, c * d - a * f,
+            d * h - e * g, b * g - a * h, a * e - b * d,
+        ];
+        let mut m = [0.0; 9];
+        for (m, adjugate) in m.iter_mut().zip(adjugate.iter()) {
+            *m = adjugate / det;
+        }
+        Some(Homography { m })
+    }
+
+    /// Transform a point, or return None if it maps to infinity or behind the viewer
+    pub fn apply(&self, x: f64, y: f64) -> Option<(f64, f64)> {
+        let m = &self.m;
+        let w = m[6] * x + m[7] * y + m[8];
+        if w <= 1e-12 {
+            return None;
+        }
+        Some(((m[0] * x + m[1] * y + m[2]) / w, (m[3] * x + m[4] * y + m[5]) / w))
+    }
+}
+
+impl Image {
+    /// Get an image of `out_w` by `out_h` pixels with this image stretched onto a quadrilateral,
+    /// given by where its top left, top right, bottom right and bottom left corners go.
+    /// The quadrilateral must be convex. Its edges are anti-aliased and the rest is transparent
+    pub fn warp_perspective(&self, corners: [(f64, f64); 4], out_w: u32, out_h: u32, interpolation: Interpolation) -> Image {
+        let (w, h) = (self.w as f64, self.h as f64);
+        match Homography::from_points([(0.0, 0.0), (w, 0.0), (w, h), (0.0, h)], corners) {
+            Some(homography) => self.warp(&homography, out_w, out_h, interpolation),
+            None => self.with_data(out_w, out_h, vec![Color { data: 0 }; out_w as usize * out_h as usize].into_boxed_slice()),
+        }
+    }
+
+    /// Get an image of `out_w` by `out_h` pixels with this image moved by a homography
+    /// from its coordinates to the output's, with anti-aliased edges
+    pub fn warp(&self, homography: &Homography, out_w: u32, out_h: u32, interpolation: Interpolation) -> Image {
+        let inverse = homography.invert();
+        let (w, h) = (self.w as f64, self.h as f64);
+        let corners = [(0.0, 0.0), (w, 0.0), (w, h), (0.0, h)];
+        let mut quad = [(0.0, 0.0); 4];
+        let mut edges = true;
+        for (corner, &(x, y)) in quad.iter_mut().zip(corners.iter()) {
+            match homography.apply(x, y) {
+                Some(point) => *corner = point,
+                // Part of the image is behind the viewer, so it has no edge to smooth
+                None => edges = false,
+            }
+        }
+
+        self.map_pixels(out_w, out_h, interpolation, |x, y| {
+            let (sx, sy) = inverse?.apply(x, y)?;
+            let coverage = if edges {
+                coverage(&quad, x, y)
+            } else if sx >= 0.0 && sy >= 0.0 && sx < w && sy < h {
+                1.0
+            } else {
+                0.0
+            };
+            if coverage <= 0.0 {
+                return None;
+            }
+            // Read the nearest point inside the image, the coverage fades the edges
+            Some((sx.clamp(0.5, (w - 0.5).max(0.5)), sy.clamp(0.5, (h - 0.5).max(0.5)), coverage))
+        })
+    }
+}
+
+/// Approximate fraction of a pixel centered at x, y inside a convex quadrilateral,
+/// from the distance to each edge
+fn coverage(quad: &[(f64, f64); 4], x: f64, y: f64) -> f32 {
+    // Twice the signed area gives the winding direction
+    let mut area = 0.0;
+    for i in 0..4 {
+        let (x0, y0) = quad[i];
+        let (x1, y1) = quad[(i + 1) % 4];
+        area += x0 * y1 - x1 * y0;
+    }
+    let winding = if area < 0.0 { -1.0 } else { 1.0 };
+
+    let mut coverage = 1.0;
+    for i in 0..4 {
+        let (x0, y0) = quad[i];
+        let (x1, y1) = quad[(i + 1) % 4];
+        let len = (x1 - x0).hypot(y1 - y0);
+        if len < 1e-12 {
+            continue;
+        }
+        let distance = winding * ((x1 - x0) * (y - y0) - (y1 - y0) * (x - x0)) / len;
+        coverage *= (distance + 0.5).clamp(0.0, 1.0);
+    }
+    coverage as f32
+}
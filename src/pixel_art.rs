@@ -0,0 +1,104 @@
+use orbclient::Color;
+
+use {Error, Image, Result};
+
+impl Image {
+    /// Get a copy of the image enlarged by a whole factor, repeating every pixel into a
+    /// square. Unlike `resize`, edges stay exactly as sharp as in the original
+    pub fn scale_nearest(&self, factor: u32) -> Result<Image> {
+        let (w, h) = self.scaled_dimensions(factor)?;
+        let f = factor as usize;
+        let mut data = Vec::with_capacity(w as usize * h as usize);
+        for row in self.data.chunks(self.w.max(1) as usize) {
+            let start = data.len();
+            for color in row {
+                data.extend((0..f).map(|_| *color));
+            }
+            for _ in 1..f {
+                data.extend_from_within(start..start + w as usize);
+            }
+        }
+        Ok(self.with_data(w, h, data.into_boxed_slice()))
+    }
+
+    /// Get a copy of the image doubled in size with the Scale2x algorithm, which rounds
+    /// off the staircase of diagonal edges in pixel art without adding new colors
+    pub fn scale2x(&self) -> Result<Image> {
+        self.scale_neighbours(2, |[_, b, _, d, e, f, _, h, _], out| {
+            if b != h && d != f {
+                out[0] = if d == b { d } else { e };
+                out[1] = if b == f { f } else { e };
+                out[2] = if d == h { d } else { e };
+                out[3] = if h == f { f } else { e };
+            } else {
+                out.fill(e);
+            }
+        })
+    }
+
+    /// Get a copy of the image tripled in size with the Scale3x algorithm
+    pub fn scale3x(&self) -> Result<Image> {
+        self.scale_neighbours(3, |[a, b, c, d, e, f, g, h, i], out| {
+            if b != h && d != f {
+                out[0] = if d == b { d } else { e };
+                out[1] = if (d == b && e != c) || (b == f && e != a) { b } else { e };
+                out[2] = if b == f { f } else { e };
+                out[3] = if (d == b && e != g) || (d == h && e != a) { d } else { e };
+                out[4] = e;
+                out[5] = if (b == f && e != i) || (h == f && e != c) { f } else { e };
+                out[6] = if d == h { d } else { e };
+                out[7] = if (d == h && e != i) || (h == f && e != g) { h } else { e };
+                out[8] = if h == f { f } else { e };
+            } else {
+                out.fill(e);
+            }
+        })
+    }
+
+    /// Get a copy of the image four times the size, by applying Scale2x twice
+    pub fn scale4x(&self) -> Result<Image> {
+        self.scale2x()?.scale2x()
+    }
+
+    fn scaled_dimensions(&self, factor: u32) -> Result<(u32, u32)> {
+        match (self.w.checked_mul(factor), self.h.checked_mul(factor)) {
+            (Some(w), Some(h)) => {
+                ::pixel_count(w, h)?;
+                Ok((w, h))
+            },
+            _ => Err(Error::SizeOverflow { width: self.w, height: self.h }),
+        }
+    }
+
+    /// Enlarge by a factor, where `f` fills the `factor * factor` block for each pixel from
+    /// its 3x3 neighbourhood. Neighbours beyond the edges repeat the edge pixels
+    fn scale_neighbours<F: Fn([u32; 9], &mut [u32])>(&self, factor: u32, f: F) -> Result<Image> {
+        let (w, h) = self.scaled_dimensions(factor)?;
+        let mut data = vec![Color { data: 0 }; w as usize * h as usize];
+        let (src_w, src_h) = (self.w as usize, self.h as usize);
+        let n = factor as usize;
+        let at = |x: usize, y: usize| self.data[y * src_w + x].data;
+
+        let mut block = vec![0; n * n];
+        for y in 0..src_h {
+            let (up, down) = (y.saturating_sub(1), (y + 1).min(src_h - 1));
+            for x in 0..src_w {
+                let (left, right) = (x.saturating_sub(1), (x + 1).min(src_w - 1));
+                f([
+                    at(left, up), at(x, up), at(right, up),
+                    at(left, y), at(x, y), at(right, y),
+                    at(left, down), at(x, down), at(right, down),
+                ], &mut block);
+
+                for (row, colors) in block.chunks(n).enumerate() {
+                    let start = (y * n + row) * w as usize + x * n;
+                    for (dst, &color) in data[start..start + n].iter_mut().zip(colors) {
+                        dst.data = color;
+                    }
+                }
+            }
+        }
+
+        Ok(self.with_data(w, h, data.into_boxed_slice()))
+    }
+}
@@ -0,0 +1,344 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read, Seek};
+use std::path::Path;
+
+use image::{self, ColorType, ImageDecoder, ImageError};
+
+use {Error, ImageFormat, Result};
+
+/// Image properties read from file headers, without decoding pixels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImageInfo {
+    pub width: u32,
+    pub height: u32,
+    pub format: ImageFormat,
+    /// Whether the image has an alpha channel or transparent palette entries
+    pub has_alpha: bool,
+    /// Bits per channel
+    pub bit_depth: u8,
+    /// Number of animation frames, 1 for still images
+    pub frame_count: u32,
+}
+
+/// Read the properties of an image file. The format is detected from the file contents,
+/// falling back to the file extension for formats without magic bytes
+pub fn probe<P: AsRef<Path>>(path: P) -> Result<ImageInfo> {
+    let path = path.as_ref();
+    let mut reader = BufReader::new(File::open(path)?);
+    let format = ::path_format(&mut reader, path)?;
+    probe_format(reader, format)
+}
+
+/// Read the properties of an encoded image in memory
+pub fn probe_bytes(data: &[u8]) -> Result<ImageInfo> {
+    probe_reader(Cursor::new(data))
+}
+
+/// Read the properties of an encoded image from any seekable reader
+pub fn probe_reader<R: Read + Seek>(reader: R) -> Result<ImageInfo> {
+    let mut reader = BufReader::new(reader);
+    let format = ::sniff_format(&mut reader)?
+        .ok_or_else(|| Error::UnsupportedFormat("unrecognized magic bytes".to_string()))?;
+    probe_format(reader, format)
+}
+
+fn probe_format<R: BufRead + Seek>(reader: R, format: ImageFormat) -> Result<ImageInfo> {
+    match format {
+        ImageFormat::Png => probe_png(reader),
+        ImageFormat::Gif => probe_gif(reader).map(|(info, _)| info),
+        ImageFormat::Jpeg => from_decoder(image::jpeg::JPEGDecoder::new(reader)?, format),
+        ImageFormat::Bmp => from_decoder(image::bmp::BMPDecoder::new(reader)?, format),
+        ImageFormat::Ico => from_decoder(image::ico::ICODecoder::new(reader)?, format),
+        ImageFormat::Tiff => from_decoder(image::tiff::TIFFDecoder::new(reader)?, format),
+        ImageFormat::Webp => probe_webp(reader),
+        ImageFormat::Pnm => from_decoder(image::pnm::PNMDecoder::new(reader)?, format),
+        ImageFormat::Hdr => from_decoder(image::hdr::HDRAdapter::new(reader)?, format),
+        ImageFormat::Tga => from_decoder(image::tga::TGADecoder::new(reader)?, format),
+    }
+}
+
+fn from_decoder<D: ImageDecoder>(decoder: D, format: ImageFormat) -> Result<ImageInfo> {
+    let (width, height) = decoder.dimensions();
+    let (has_alpha, bit_depth) = match decoder.colortype() {
+        ColorType::Gray(bits) | ColorType::RGB(bits) | ColorType::BGR(bits) | ColorType::Palette(bits) => (false, bits),
+        ColorType::GrayA(bits) | ColorType::RGBA(bits) | ColorType::BGRA(bits) => (true, bits),
+    };
+
+    Ok(ImageInfo {
+        width: width as u32,
+        height: height as u32,
+        format,
+        has_alpha,
+        bit_depth,
+        frame_count: 1,
+    })
+}
+
+fn format_error(message: &str) -> Error {
+    Error::Decode(ImageError::FormatError(message.to_string()))
+}
+
+fn read_array<R: Read, const N: usize>(reader: &mut R) -> Result<[u8; N]> {
+    let mut buf = [0; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Walk the PNG chunks up to the image data, reading the header, transparency and animation control
+fn probe_png<R: Read>(reader: R) -> Result<ImageInfo> {
+    let mut chunks = PngChunks::new(reader)?;
+
+    let mut info = None;
+    loop {
+        match &chunks.next_chunk()? {
+            b"IHDR" => {
+                let ihdr: [u8; 13] = chunks.read_array()?;
+                info = Some(ImageInfo {
+                    width: u32::from_be_bytes([ihdr[0], ihdr[1], ihdr[2], ihdr[3]]),
+                    height: u32::from_be_bytes([ihdr[4], ihdr[5], ihdr[6], ihdr[7]]),
+                    format: ImageFormat::Png,
+                    // Gray with alpha or RGBA
+                    has_alpha: ihdr[9] == 4 || ihdr[9] == 6,
+                    bit_depth: ihdr[8],
+                    frame_count: 1,
+                });
+            },
+            b"acTL" => {
+                let frames = chunks.read_array()?;
+                if let Some(ref mut info) = info {
+                    info.frame_count = u32::from_be_bytes(frames);
+                }
+            },
+            b"tRNS" => if let Some(ref mut info) = info {
+                info.has_alpha = true;
+            },
+            b"IDAT" | b"IEND" => break,
+            _ => (),
+        }
+    }
+
+    info.ok_or_else(|| format_error("IHDR chunk missing"))
+}
+
+/// Reader over the chunks of a PNG file that does not check CRCs
+pub(crate) struct PngChunks<R> {
+    reader: R,
+    // Bytes left in the current chunk, including its CRC
+    remaining: u64,
+}
+
+impl<R: Read> PngChunks<R> {
+    /// Skip the PNG signature at the start of the reader
+    pub(crate) fn new(mut reader: R) -> Result<Self> {
+        skip(&mut reader, 8)?;
+        Ok(PngChunks { reader, remaining: 0 })
+    }
+
+    /// Skip the rest of the current chunk and return the type of the next one
+    pub(crate) fn next_chunk(&mut self) -> Result<[u8; 4]> {
+        skip(&mut self.reader, self.remaining)?;
+        let [l0, l1, l2, l3, k0, k1, k2, k3] = read_array(&mut self.reader)?;
+        self.remaining = u32::from_be_bytes([l0, l1, l2, l3]) as u64 + 4;
+        Ok([k0, k1, k2, k3])
+    }
+
+    /// Read the next bytes of the current chunk
+    pub(crate) fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        if (N as u64) + 4 > self.remaining {
+            return Err(format_error("PNG chunk too short"));
+        }
+        self.remaining -= N as u64;
+        read_array(&mut self.reader)
+    }
+
+    /// Read the rest of the current chunk
+    pub(crate) fn read_data(&mut self) -> Result<Vec<u8>> {
+        let len = self.remaining.saturating_sub(4);
+        let mut data = Vec::new();
+        if self.reader.by_ref().take(len).read_to_end(&mut data)? < len as usize {
+            return Err(Error::Io(io::ErrorKind::UnexpectedEof.into()));
+        }
+        self.remaining -= len;
+        Ok(data)
+    }
+}
+
+/// Walk the GIF blocks, counting frames and looking for transparency without decompressing.
+/// Also returns the repeat count of the NETSCAPE2.0 application extension, if present
+pub(crate) fn probe_gif<R: Read>(mut reader: R) -> Result<(ImageInfo, Option<u16>)> {
+    let header: [u8; 13] = read_array(&mut reader)?;
+    let width = u16::from_le_bytes([header[6], header[7]]) as u32;
+    let height = u16::from_le_bytes([header[8], header[9]]) as u32;
+    skip_color_table(&mut reader, header[10])?;
+
+    let mut has_alpha = false;
+    let mut frame_count = 0;
+    let mut repeats = None;
+    loop {
+        let [block] = read_array(&mut reader)?;
+        match block {
+            // Extension
+            0x21 => {
+                let [label] = read_array(&mut reader)?;
+                match label {
+                    // Graphic control extension, the lowest flag bit marks a transparent index
+                    0xF9 => {
+                        let [_size, flags, _, _, _] = read_array(&mut reader)?;
+                        has_alpha |= flags & 1 != 0;
+                    },
+                    // Application extension
+                    0xFF => {
+                        let [size] = read_array(&mut reader)?;
+                        let mut id = vec![0; size as usize];
+                        reader.read_exact(&mut id)?;
+                        if id == b"NETSCAPE2.0" {
+                            repeats = read_netscape_repeats(&mut reader)?.or(repeats);
+                            continue;
+                        }
+                    },
+                    _ => (),
+                }
+                skip_sub_blocks(&mut reader)?;
+            },
+            // Image descriptor
+            0x2C => {
+                let descriptor: [u8; 9] = read_array(&mut reader)?;
+                skip_color_table(&mut reader, descriptor[8])?;
+                // LZW minimum code size
+                skip(&mut reader, 1)?;
+                skip_sub_blocks(&mut reader)?;
+                frame_count += 1;
+            },
+            // Trailer
+            0x3B => break,
+            _ => return Err(format_error("unknown GIF block")),
+        }
+    }
+
+    let info = ImageInfo {
+        width,
+        height,
+        format: ImageFormat::Gif,
+        has_alpha,
+        bit_depth: 8,
+        frame_count,
+    };
+    Ok((info, repeats))
+}
+
+/// Read the sub-blocks of a NETSCAPE2.0 extension, returning the repeat count of the loop sub-block
+fn read_netscape_repeats<R: Read>(reader: &mut R) -> Result<Option<u16>> {
+    let mut repeats = None;
+    loop {
+        let [len] = read_array(reader)?;
+        if len == 0 {
+            return Ok(repeats);
+        }
+        let mut data = vec![0; len as usize];
+        reader.read_exact(&mut data)?;
+        if len >= 3 && data[0] == 1 {
+            repeats = Some(u16::from_le_bytes([data[1], data[2]]));
+        }
+    }
+}
+
+/// Read the size and alpha from the first WebP chunk, counting frames of animated files
+fn probe_webp<R: Read>(mut reader: R) -> Result<ImageInfo> {
+    let riff: [u8; 12] = read_array(&mut reader)?;
+    // The RIFF size counts from the WEBP form type
+    let mut remaining = (u32::from_le_bytes([riff[4], riff[5], riff[6], riff[7]]) as u64).saturating_sub(4);
+
+    let mut info: Option<ImageInfo> = None;
+    let mut animated = false;
+    let mut frame_count = 0;
+    while remaining >= 8 {
+        let [k0, k1, k2, k3, l0, l1, l2, l3] = read_array(&mut reader)?;
+        let len = u32::from_le_bytes([l0, l1, l2, l3]) as u64;
+        // Chunks are padded to an even length
+        let padded = len + (len & 1);
+        remaining = remaining.saturating_sub(8 + padded);
+
+        let webp_info = |width: u32, height: u32, has_alpha: bool| Some(ImageInfo {
+            width,
+            height,
+            format: ImageFormat::Webp,
+            has_alpha,
+            bit_depth: 8,
+            frame_count: 1,
+        });
+        let read = match &[k0, k1, k2, k3] {
+            // Lossy frame header, after the 3 byte frame tag and start code
+            b"VP8 " if info.is_none() && len >= 10 => {
+                let header: [u8; 10] = read_array(&mut reader)?;
+                if header[3..6] != [0x9D, 0x01, 0x2A] {
+                    return Err(format_error("invalid VP8 start code"));
+                }
+                let width = u16::from_le_bytes([header[6], header[7]]) & 0x3FFF;
+                let height = u16::from_le_bytes([header[8], header[9]]) & 0x3FFF;
+                info = webp_info(width as u32, height as u32, false);
+                10
+            },
+            // Lossless header, 14 bits each for width and height minus one, then the alpha hint
+            b"VP8L" if info.is_none() && len >= 5 => {
+                let [signature, b0, b1, b2, b3] = read_array(&mut reader)?;
+                if signature != 0x2F {
+                    return Err(format_error("invalid VP8L signature"));
+                }
+                let bits = u32::from_le_bytes([b0, b1, b2, b3]);
+                info = webp_info((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1, bits & (1 << 28) != 0);
+                5
+            },
+            // Extended header with feature flags and 24 bit canvas size minus one
+            b"VP8X" if info.is_none() && len >= 10 => {
+                let header: [u8; 10] = read_array(&mut reader)?;
+                let width = u32::from_le_bytes([header[4], header[5], header[6], 0]) + 1;
+                let height = u32::from_le_bytes([header[7], header[8], header[9], 0]) + 1;
+                info = webp_info(width, height, header[0] & 0x10 != 0);
+                animated = header[0] & 0x02 != 0;
+                10
+            },
+            b"ANMF" => {
+                frame_count += 1;
+                0
+            },
+            _ => 0,
+        };
+
+        // Only animated files need more than the first chunk
+        if info.is_some() && !animated {
+            break;
+        }
+        skip(&mut reader, padded - read)?;
+    }
+
+    let mut info = info.ok_or_else(|| format_error("WebP image header missing"))?;
+    if animated {
+        info.frame_count = frame_count;
+    }
+    Ok(info)
+}
+
+/// Skip over bytes, failing if the data ends early
+fn skip<R: Read>(reader: &mut R, len: u64) -> Result<()> {
+    if io::copy(&mut reader.by_ref().take(len), &mut io::sink())? < len {
+        return Err(Error::Io(io::ErrorKind::UnexpectedEof.into()));
+    }
+    Ok(())
+}
+
+fn skip_color_table<R: Read>(reader: &mut R, flags: u8) -> Result<()> {
+    if flags & 0x80 != 0 {
+        skip(reader, 3 << ((flags & 0x07) + 1))?;
+    }
+    Ok(())
+}
+
+fn skip_sub_blocks<R: Read>(reader: &mut R) -> Result<()> {
+    loop {
+        let [len] = read_array(reader)?;
+        if len == 0 {
+            return Ok(());
+        }
+        skip(reader, len as u64)?;
+    }
+}
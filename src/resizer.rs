@@ -0,0 +1,167 @@
+use std::{mem, slice};
+
+use orbclient::Color;
+use resize::{self, Pixel};
+
+use {alpha, linear, AlphaMode, Error, Image, ResizeType, Result};
+
+/// Filters planned by the resize crate, working on 8 bit sRGB or 16 bit linear channels
+enum Plan {
+    Srgb(resize::Resizer<Pixel::RGBA>),
+    Linear(resize::Resizer<Pixel::RGBA64>),
+}
+
+/// Resizes images of one size to another, keeping the filter weights and scratch buffers
+/// between calls. Create one per pair of sizes and reuse it for every frame or redraw
+pub struct Resizer {
+    src_w: u32,
+    src_h: u32,
+    dst_w: u32,
+    dst_h: u32,
+    // Built in filter the plan was made with, or None for a custom filter, which cannot be compared
+    filter: Option<mem::Discriminant<ResizeType>>,
+    plan: Plan,
+    // Premultiplied copy of the source, or linear light channels of the source and destination
+    colors: Vec<Color>,
+    src_channels: Vec<u16>,
+    dst_channels: Vec<u16>,
+}
+
+impl Resizer {
+    /// Plan resizing from one size to another, filtering sRGB encoded values
+    pub fn new(src_w: u32, src_h: u32, dst_w: u32, dst_h: u32, resize_type: ResizeType) -> Result<Self> {
+        Self::check(src_w, src_h, dst_w, dst_h)?;
+        let filter = filter_key(&resize_type);
+        let plan = Plan::Srgb(resize::new(src_w as usize, src_h as usize,
+                                          dst_w as usize, dst_h as usize,
+                                          Pixel::RGBA, resize_type));
+        Ok(Self::with_plan(src_w, src_h, dst_w, dst_h, filter, plan))
+    }
+
+    /// Plan resizing from one size to another, filtering in linear light like `Image::resize_linear`
+    pub fn new_linear(src_w: u32, src_h: u32, dst_w: u32, dst_h: u32, resize_type: ResizeType) -> Result<Self> {
+        Self::check(src_w, src_h, dst_w, dst_h)?;
+        let filter = filter_key(&resize_type);
+        let plan = Plan::Linear(resize::new(src_w as usize, src_h as usize,
+                                            dst_w as usize, dst_h as usize,
+                                            Pixel::RGBA64, resize_type));
+        Ok(Self::with_plan(src_w, src_h, dst_w, dst_h, filter, plan))
+    }
+
+    fn check(src_w: u32, src_h: u32, dst_w: u32, dst_h: u32) -> Result<()> {
+        if ::pixel_count(src_w, src_h)? == 0 && ::pixel_count(dst_w, dst_h)? > 0 {
+            return Err(Error::Resize("cannot resize an empty image".to_string()));
+        }
+        Ok(())
+    }
+
+    fn with_plan(src_w: u32, src_h: u32, dst_w: u32, dst_h: u32,
+                 filter: Option<mem::Discriminant<ResizeType>>, plan: Plan) -> Self {
+        Resizer {
+            src_w,
+            src_h,
+            dst_w,
+            dst_h,
+            filter,
+            plan,
+            colors: Vec::new(),
+            src_channels: Vec::new(),
+            dst_channels: Vec::new(),
+        }
+    }
+
+    /// Get the width and height of the images this resizer accepts
+    pub fn source_size(&self) -> (u32, u32) {
+        (self.src_w, self.src_h)
+    }
+
+    /// Get the width and height of the images this resizer produces
+    pub fn destination_size(&self) -> (u32, u32) {
+        (self.dst_w, self.dst_h)
+    }
+
+    /// Check whether `Resizer::new` with these sizes and filter would make the same plan.
+    /// A resizer with a custom filter never matches
+    pub(crate) fn is_srgb_plan(&self, src: (u32, u32), dst: (u32, u32), resize_type: &ResizeType) -> bool {
+        let srgb = match self.plan {
+            Plan::Srgb(_) => true,
+            Plan::Linear(_) => false,
+        };
+        srgb && self.source_size() == src && self.destination_size() == dst
+            && self.filter.is_some() && self.filter == filter_key(resize_type)
+    }
+
+    /// Get a resized copy of an image
+    pub fn resize(&mut self, src: &Image) -> Result<Image> {
+        let mut dst = src.with_data(0, 0, Box::new([]));
+        self.resize_into(src, &mut dst)?;
+        Ok(dst)
+    }
+
+    /// Resize an image into another, reusing its pixel buffer if it already has the
+    /// destination size. The destination takes the alpha mode of the source
+    pub fn resize_into(&mut self, src: &Image, dst: &mut Image) -> Result<()> {
+        if (src.w, src.h) != (self.src_w, self.src_h) {
+            return Err(Error::Resize(format!(
+                "resizer expects a {}x{} image, got {}x{}",
+                self.src_w, self.src_h, src.w, src.h
+            )));
+        }
+
+        let len = self.dst_w as usize * self.dst_h as usize;
+        if dst.data.len() != len {
+            dst.data = vec![Color { data: 0 }; len].into_boxed_slice();
+        }
+        dst.w = self.dst_w;
+        dst.h = self.dst_h;
+        dst.alpha = src.alpha;
+        if len == 0 {
+            return Ok(());
+        }
+
+        match self.plan {
+            Plan::Srgb(ref mut plan) => {
+                // Filter premultiplied colors, so transparent pixels do not darken their neighbours
+                let straight = src.alpha == AlphaMode::Straight;
+                let src_color = if straight {
+                    self.colors.clear();
+                    self.colors.extend(src.data.iter().map(|&color| alpha::premultiply(color)));
+                    &self.colors[..]
+                } else {
+                    &src.data[..]
+                };
+
+                plan.resize(bytes(src_color), bytes_mut(&mut dst.data));
+
+                if straight {
+                    for color in dst.data.iter_mut() {
+                        *color = alpha::unpremultiply(*color);
+                    }
+                }
+            },
+            Plan::Linear(ref mut plan) => {
+                linear::to_linear(&src.data, src.alpha, &mut self.src_channels);
+                self.dst_channels.resize(len * 4, 0);
+                plan.resize(&self.src_channels, &mut self.dst_channels);
+                linear::from_linear(&self.dst_channels, &mut dst.data, src.alpha);
+            },
+        }
+
+        Ok(())
+    }
+}
+
+fn filter_key(resize_type: &ResizeType) -> Option<mem::Discriminant<ResizeType>> {
+    match *resize_type {
+        ResizeType::Custom(_) => None,
+        ref resize_type => Some(mem::discriminant(resize_type)),
+    }
+}
+
+fn bytes(colors: &[Color]) -> &[u8] {
+    unsafe { slice::from_raw_parts(colors.as_ptr() as *const u8, mem::size_of_val(colors)) }
+}
+
+fn bytes_mut(colors: &mut [Color]) -> &mut [u8] {
+    unsafe { slice::from_raw_parts_mut(colors.as_mut_ptr() as *mut u8, mem::size_of_val(colors)) }
+}
@@ -0,0 +1,401 @@
+use std::cell::Cell;
+use std::cmp;
+
+use orbclient::{Color, Mode, Renderer};
+
+use {AlphaMode, Image};
+
+/// A piece of an image that can be drawn without copying
+pub struct ImageRoi<'a> {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+    image: &'a Image
+}
+
+impl<'a> ImageRoi<'a> {
+    pub(crate) fn new(image: &'a Image, x: u32, y: u32, w: u32, h: u32) -> Self {
+        let (x, y, w, h) = clamp_rect(image, x, y, w, h);
+        ImageRoi {
+            x,
+            y,
+            w,
+            h,
+            image
+        }
+    }
+
+    /// Horizontal position of the ROI within the image
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    /// Vertical position of the ROI within the image
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+
+    /// Width of the ROI in pixels
+    pub fn width(&self) -> u32 {
+        self.w
+    }
+
+    /// Height of the ROI in pixels
+    pub fn height(&self) -> u32 {
+        self.h
+    }
+
+    /// Get a piece of the ROI, with coordinates relative to the ROI
+    pub fn roi(&self, x: u32, y: u32, w: u32, h: u32) -> ImageRoi<'a> {
+        let x1 = cmp::min(x, self.w);
+        let y1 = cmp::min(y, self.h);
+        let x2 = cmp::max(x1, cmp::min(x.saturating_add(w), self.w));
+        let y2 = cmp::max(y1, cmp::min(y.saturating_add(h), self.h));
+
+        ImageRoi {
+            x: self.x + x1,
+            y: self.y + y1,
+            w: x2 - x1,
+            h: y2 - y1,
+            image: self.image
+        }
+    }
+
+    /// Copy the ROI into a new image
+    pub fn to_image(&self) -> Image {
+        let stride = self.image.w as usize;
+        let mut data = Vec::with_capacity(self.w as usize * self.h as usize);
+        for row in 0..self.h as usize {
+            let start = (self.y as usize + row) * stride + self.x as usize;
+            data.extend_from_slice(&self.image.data[start..start + self.w as usize]);
+        }
+
+        self.image.with_data(self.w, self.h, data.into_boxed_slice())
+    }
+
+    /// Draw the ROI on a window, clipped to the bounds of the window
+    pub fn draw<R: Renderer>(&self, renderer: &mut R, x: i32, y: i32) {
+        if self.image.alpha == AlphaMode::Premultiplied {
+            let mut straight = self.to_image();
+            straight.unpremultiply();
+            return straight.roi(0, 0, self.w, self.h).draw(renderer, x, y);
+        }
+
+        let dst_w = renderer.width();
+        let dst_h = renderer.height();
+        let x1 = cmp::max(x as i64, 0);
+        let y1 = cmp::max(y as i64, 0);
+        let x2 = cmp::min(x as i64 + self.w as i64, dst_w as i64);
+        let y2 = cmp::min(y as i64 + self.h as i64, dst_h as i64);
+        if x1 >= x2 || y1 >= y2 {
+            return;
+        }
+
+        let (w, h) = ((x2 - x1) as usize, (y2 - y1) as usize);
+        let (x1, y1) = (x1 as usize, y1 as usize);
+        let stride = self.image.w as usize;
+        let src_x = self.x as usize + (x1 as i64 - x as i64) as usize;
+        let src_y = self.y as usize + (y1 as i64 - y as i64) as usize;
+        let src_row = |row: usize| {
+            let start = (src_y + row) * stride + src_x;
+            &self.image.data[start..start + w]
+        };
+
+        let replace = match renderer.mode().get() {
+            Mode::Blend => false,
+            Mode::Overwrite => true,
+        };
+        let dst_stride = dst_w as usize;
+        if renderer.data().len() == dst_stride * dst_h as usize {
+            let data = renderer.data_mut();
+            for row in 0..h {
+                let start = (y1 + row) * dst_stride + x1;
+                let dst = &mut data[start..start + w];
+                if replace {
+                    dst.copy_from_slice(src_row(row));
+                } else {
+                    for (old, new) in dst.iter_mut().zip(src_row(row)) {
+                        blend(old, *new);
+                    }
+                }
+            }
+        } else {
+            // The renderer does not use its width as stride, so let it place each row
+            for row in 0..h {
+                renderer.image(x1 as i32, (y1 + row) as i32, w as u32, 1, src_row(row));
+            }
+        }
+    }
+}
+
+/// Clamp a rectangle to the bounds of an image
+fn clamp_rect(image: &Image, x: u32, y: u32, w: u32, h: u32) -> (u32, u32, u32, u32) {
+    let x1 = cmp::min(x, image.w);
+    let y1 = cmp::min(y, image.h);
+    let x2 = cmp::max(x1, cmp::min(x.saturating_add(w), image.w));
+    let y2 = cmp::max(y1, cmp::min(y.saturating_add(h), image.h));
+    (x1, y1, x2 - x1, y2 - y1)
+}
+
+/// A mutable piece of an image that can be drawn into with `Renderer`.
+/// Drawing is clipped to the piece, and coordinates are relative to its top left corner
+pub struct ImageRoiMut<'a> {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+    image: &'a mut Image
+}
+
+impl<'a> ImageRoiMut<'a> {
+    pub(crate) fn new(image: &'a mut Image, x: u32, y: u32, w: u32, h: u32) -> Self {
+        let (x, y, w, h) = clamp_rect(image, x, y, w, h);
+        ImageRoiMut {
+            x,
+            y,
+            w,
+            h,
+            image
+        }
+    }
+
+    /// Number of pixels between the starts of two rows in `data` and `data_mut`
+    pub fn stride(&self) -> u32 {
+        self.image.w
+    }
+
+    /// Return a reference to a row of the ROI
+    pub fn row(&self, y: u32) -> &[Color] {
+        let start = self.offset(0, y);
+        &self.image.data[start..start + self.w as usize]
+    }
+
+    /// Return a mutable reference to a row of the ROI
+    pub fn row_mut(&mut self, y: u32) -> &mut [Color] {
+        let start = self.offset(0, y);
+        &mut self.image.data[start..start + self.w as usize]
+    }
+
+    /// The pixels from the first to the last of the ROI, with the parent image stride
+    pub(crate) fn region_mut(&mut self) -> (&mut [Color], usize) {
+        let stride = self.image.w as usize;
+        if self.w == 0 || self.h == 0 {
+            return (&mut [], stride);
+        }
+        let start = self.offset(0, 0);
+        let end = self.offset(self.w - 1, self.h - 1) + 1;
+        (&mut self.image.data[start..end], stride)
+    }
+
+    /// Get how the colors of the parent image are stored
+    pub fn alpha_mode(&self) -> AlphaMode {
+        self.image.alpha
+    }
+
+    /// Whether drawing overwrites pixels instead of blending
+    fn replace(&self) -> bool {
+        match self.image.mode.get() {
+            Mode::Blend => false,
+            Mode::Overwrite => true,
+        }
+    }
+
+    fn offset(&self, x: u32, y: u32) -> usize {
+        (self.y + y) as usize * self.image.w as usize + (self.x + x) as usize
+    }
+
+    /// Clip a rectangle in ROI coordinates, returning its visible part as (x, y, w, h)
+    fn clip(&self, x: i32, y: i32, w: u32, h: u32) -> Option<(u32, u32, u32, u32)> {
+        let x1 = cmp::max(x as i64, 0);
+        let y1 = cmp::max(y as i64, 0);
+        let x2 = cmp::min(x as i64 + w as i64, self.w as i64);
+        let y2 = cmp::min(y as i64 + h as i64, self.h as i64);
+        if x1 < x2 && y1 < y2 {
+            Some((x1 as u32, y1 as u32, (x2 - x1) as u32, (y2 - y1) as u32))
+        } else {
+            None
+        }
+    }
+
+    /// Copy or blend rows of colors into the ROI, clipping to its bounds
+    fn blit(&mut self, x: i32, y: i32, w: u32, h: u32, data: &[Color], replace: bool) {
+        let (cx, cy, cw, ch) = match self.clip(x, y, w, h) {
+            Some(clipped) => clipped,
+            None => return
+        };
+
+        // Offset of the visible part within the source data
+        let skip_x = (cx as i64 - x as i64) as usize;
+        let skip_y = (cy as i64 - y as i64) as usize;
+        for row in 0..ch as usize {
+            let src_start = (skip_y + row) * w as usize + skip_x;
+            if src_start >= data.len() {
+                break;
+            }
+            let src = &data[src_start..cmp::min(src_start + cw as usize, data.len())];
+            let dst_start = self.offset(cx, cy + row as u32);
+            let dst = &mut self.image.data[dst_start..dst_start + src.len()];
+            if replace {
+                dst.copy_from_slice(src);
+            } else {
+                for (old, new) in dst.iter_mut().zip(src) {
+                    blend(old, *new);
+                }
+            }
+        }
+    }
+}
+
+/// Blend a color over a pixel, the same way `Renderer::pixel` does
+fn blend(old: &mut Color, new: Color) {
+    let new = new.data;
+    let alpha = (new >> 24) & 0xFF;
+    if alpha >= 255 {
+        old.data = new;
+    } else if alpha > 0 {
+        let old = &mut old.data;
+        let n_alpha = 255 - alpha;
+        let rb = ((n_alpha * (*old & 0x00FF00FF)) + (alpha * (new & 0x00FF00FF))) >> 8;
+        let ag = (n_alpha * ((*old & 0xFF00FF00) >> 8))
+            + (alpha * (0x01000000 | ((new & 0x0000FF00) >> 8)));
+
+        *old = (rb & 0x00FF00FF) | (ag & 0xFF00FF00);
+    }
+}
+
+impl<'a> Renderer for ImageRoiMut<'a> {
+    /// Get the width of the ROI in pixels
+    fn width(&self) -> u32 {
+        self.w
+    }
+
+    /// Get the height of the ROI in pixels
+    fn height(&self) -> u32 {
+        self.h
+    }
+
+    /// Return a reference to the colors from the first to the last pixel of the ROI.
+    /// Rows are `stride` pixels apart
+    fn data(&self) -> &[Color] {
+        if self.w == 0 || self.h == 0 {
+            return &[];
+        }
+        let start = self.offset(0, 0);
+        let end = self.offset(self.w - 1, self.h - 1) + 1;
+        &self.image.data[start..end]
+    }
+
+    /// Return a mutable reference to the colors from the first to the last pixel of the ROI.
+    /// Rows are `stride` pixels apart
+    fn data_mut(&mut self) -> &mut [Color] {
+        if self.w == 0 || self.h == 0 {
+            return &mut [];
+        }
+        let start = self.offset(0, 0);
+        let end = self.offset(self.w - 1, self.h - 1) + 1;
+        &mut self.image.data[start..end]
+    }
+
+    fn sync(&mut self) -> bool {
+        true
+    }
+
+    fn update(&mut self) -> bool {
+        true
+    }
+
+    fn update_rects(&mut self, _rects: &[(i32, i32, u32, u32)]) -> bool {
+        true
+    }
+
+    fn mode(&self) -> &Cell<Mode> {
+        &self.image.mode
+    }
+
+    fn pixel(&mut self, x: i32, y: i32, color: Color) {
+        if x >= 0 && y >= 0 && (x as u32) < self.w && (y as u32) < self.h {
+            let replace = self.replace();
+            let offset = self.offset(x as u32, y as u32);
+            let old = &mut self.image.data[offset];
+            if replace {
+                *old = color;
+            } else {
+                blend(old, color);
+            }
+        }
+    }
+
+    fn set(&mut self, color: Color) {
+        for y in 0..self.h {
+            for pixel in self.row_mut(y) {
+                *pixel = color;
+            }
+        }
+    }
+
+    fn rect(&mut self, x: i32, y: i32, w: u32, h: u32, color: Color) {
+        let (cx, cy, cw, ch) = match self.clip(x, y, w, h) {
+            Some(clipped) => clipped,
+            None => return
+        };
+
+        let replace = self.replace();
+        for row in cy..cy + ch {
+            let start = self.offset(cx, row);
+            for pixel in self.image.data[start..start + cw as usize].iter_mut() {
+                if replace {
+                    *pixel = color;
+                } else {
+                    blend(pixel, color);
+                }
+            }
+        }
+    }
+
+    fn box_blur(&mut self, x: i32, y: i32, w: u32, h: u32, r: i32) {
+        let (cx, cy, cw, ch) = match self.clip(x, y, w, h) {
+            Some(clipped) => clipped,
+            None => return
+        };
+
+        // Blur a contiguous copy of the region, then write it back
+        let mut region = Image::new(cw, ch);
+        for row in 0..ch {
+            let start = self.offset(cx, cy + row);
+            region.data[(row * cw) as usize..((row + 1) * cw) as usize]
+                .copy_from_slice(&self.image.data[start..start + cw as usize]);
+        }
+        region.box_blur(0, 0, cw, ch, r);
+        self.blit(cx as i32, cy as i32, cw, ch, &region.data, true);
+    }
+
+    fn image(&mut self, start_x: i32, start_y: i32, w: u32, h: u32, data: &[Color]) {
+        let replace = self.replace();
+        self.blit(start_x, start_y, w, h, data, replace);
+    }
+
+    fn image_over(&mut self, start: i32, image_data: &[Color]) {
+        let w = self.w;
+        if w > 0 {
+            let h = (image_data.len() as u32).div_ceil(w);
+            self.blit(0, start, w, h, image_data, true);
+        }
+    }
+
+    fn image_opaque(&mut self, start_x: i32, start_y: i32, w: u32, h: u32, image_data: &[Color]) {
+        self.blit(start_x, start_y, w, h, image_data, true);
+    }
+
+    fn image_fast(&mut self, start_x: i32, start_y: i32, w: u32, h: u32, image_data: &[Color]) {
+        self.blit(start_x, start_y, w, h, image_data, false);
+    }
+
+    fn getpixel(&self, x: i32, y: i32) -> Color {
+        if x >= 0 && y >= 0 && (x as u32) < self.w && (y as u32) < self.h {
+            self.image.data[self.offset(x as u32, y as u32)]
+        } else {
+            Color::rgba(0, 0, 0, 0)
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use orbclient::Color;
+
+use {Image, ResizeType, Resizer, Result};
+
+/// How an image is fitted into an area of a different size
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScaleMode {
+    /// Do not resize the image, just center it
+    Center,
+    /// Resize the image to the size of the area
+    Fill,
+    /// Resize the image - keeping its aspect ratio, and fit it to the area with blank space
+    Scale,
+    /// Resize the image - keeping its aspect ratio, and crop to remove all blank space
+    Zoom,
+    /// Do not resize the image, repeat it from the top left corner to cover the area
+    Tile,
+}
+
+impl ScaleMode {
+    /// Get the size an image of `image_w` by `image_h` pixels is resized to, to fit an area of `w` by `h`
+    pub fn scaled_size(self, image_w: u32, image_h: u32, w: u32, h: u32) -> (u32, u32) {
+        let d_w = w as f64;
+        let d_h = h as f64;
+        let i_w = image_w as f64;
+        let i_h = image_h as f64;
+
+        let scale = match self {
+            ScaleMode::Center | ScaleMode::Tile => return (image_w, image_h),
+            ScaleMode::Fill => return (w, h),
+            ScaleMode::Scale => if d_w / d_h > i_w / i_h {
+                d_h / i_h
+            } else {
+                d_w / i_w
+            },
+            ScaleMode::Zoom => if d_w / d_h < i_w / i_h {
+                d_h / i_h
+            } else {
+                d_w / i_w
+            },
+        };
+
+        ((i_w * scale) as u32, (i_h * scale) as u32)
+    }
+}
+
+/// Where to draw a scaled image: the piece `crop_x, crop_y, w, h` of it goes at `x, y` in the area
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Placement {
+    pub x: i32,
+    pub y: i32,
+    pub crop_x: u32,
+    pub crop_y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl Image {
+    /// Fit the image into an area of `w` by `h` pixels. Returns the scaled image and
+    /// the piece of it to draw where, centered and cropped to the area
+    pub fn scale_to(&self, w: u32, h: u32, mode: ScaleMode, filter: ResizeType) -> Result<(Image, Placement)> {
+        let mut scaled = self.with_data(0, 0, Box::new([]));
+        let placement = self.scale_into(w, h, mode, filter, &mut None, &mut scaled)?;
+        Ok((scaled, placement))
+    }
+
+    /// Fit the image into an area like `scale_to`, writing into `scaled` and reusing its buffer.
+    /// The resizer is kept while it is one `Resizer::new` would make for the scaled size and
+    /// the given filter, and is otherwise replaced with a new one
+    pub fn scale_into(&self, w: u32, h: u32, mode: ScaleMode, filter: ResizeType,
+                      resizer: &mut Option<Resizer>, scaled: &mut Image) -> Result<Placement> {
+        if mode == ScaleMode::Tile {
+            *scaled = self.tile(w, h);
+            return Ok(Placement { x: 0, y: 0, crop_x: 0, crop_y: 0, w, h });
+        }
+
+        let (scaled_w, scaled_h) = mode.scaled_size(self.w, self.h, w, h);
+        if (scaled_w, scaled_h) == (self.w, self.h) {
+            if scaled.data.len() == self.data.len() {
+                scaled.data.copy_from_slice(&self.data);
+            } else {
+                scaled.data = self.data.clone();
+            }
+            scaled.w = self.w;
+            scaled.h = self.h;
+            scaled.alpha = self.alpha;
+        } else {
+            let planned = resizer.as_ref().is_some_and(|resizer| {
+                resizer.is_srgb_plan((self.w, self.h), (scaled_w, scaled_h), &filter)
+            });
+            if !planned {
+                *resizer = Some(Resizer::new(self.w, self.h, scaled_w, scaled_h, filter)?);
+            }
+            if let Some(ref mut resizer) = *resizer {
+                resizer.resize_into(self, scaled)?;
+            }
+        }
+
+        let (crop_x, crop_w) = if scaled_w > w {
+            ((scaled_w - w) / 2, w)
+        } else {
+            (0, scaled_w)
+        };
+        let (crop_y, crop_h) = if scaled_h > h {
+            ((scaled_h - h) / 2, h)
+        } else {
+            (0, scaled_h)
+        };
+
+        Ok(Placement {
+            x: ((w - crop_w) / 2) as i32,
+            y: ((h - crop_h) / 2) as i32,
+            crop_x,
+            crop_y,
+            w: crop_w,
+            h: crop_h,
+        })
+    }
+
+    /// Repeat the image to cover `w` by `h` pixels
+    pub(crate) fn tile(&self, w: u32, h: u32) -> Image {
+        let mut tiled = self.with_data(w, h, vec![Color { data: 0 }; w as usize * h as usize].into_boxed_slice());
+        if self.w == 0 || self.h == 0 {
+            return tiled;
+        }
+
+        let (src_w, dst_w) = (self.w as usize, w as usize);
+        for (y, row) in tiled.data.chunks_mut(dst_w).enumerate() {
+            let src_row = &self.data[(y % self.h as usize) * src_w..][..src_w];
+            for chunk in row.chunks_mut(src_w) {
+                chunk.copy_from_slice(&src_row[..chunk.len()]);
+            }
+        }
+        tiled
+    }
+}
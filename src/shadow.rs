@@ -0,0 +1,119 @@
+use std::cmp;
+
+use orbclient::Color;
+
+use {Error, Image, Result};
+
+impl Image {
+    /// Build a shadow from the alpha channel of the image, grown by `spread` pixels and
+    /// softened over `blur_radius` pixels, then moved by `offset`.
+    ///
+    /// Only the shadow is drawn. The returned image is large enough to hold both the shadow
+    /// and the original, with the original's top left corner at
+    /// `(m + max(-offset.0, 0), m + max(-offset.1, 0))` where `m = blur_radius + spread`.
+    /// Draw the shadow at that much above and to the left of the image, then the image on top.
+    /// Fails if the shadow would be too large
+    pub fn drop_shadow(&self, offset: (i32, i32), blur_radius: u32, spread: u32, color: Color) -> Result<Image> {
+        let (dx, dy) = (offset.0.unsigned_abs(), offset.1.unsigned_abs());
+        let (margin, w, h) = self.grown_size(blur_radius, spread, dx, dy)?;
+        let left = margin as i64 + cmp::max(-(offset.0 as i64), 0);
+        let top = margin as i64 + cmp::max(-(offset.1 as i64), 0);
+
+        let mut shadow = Image::new(w, h);
+        self.shadow_mask(&mut shadow, (left + offset.0 as i64) as u32, (top + offset.1 as i64) as u32, blur_radius, spread, color);
+        Ok(shadow)
+    }
+
+    /// Build a glow around the outside of the shapes in the image, grown by `spread` pixels
+    /// and fading over `blur_radius` pixels. The glow is not drawn under the image itself.
+    ///
+    /// The returned image is `blur_radius + spread` pixels larger on every side. Fails if it
+    /// would be too large
+    pub fn outer_glow(&self, blur_radius: u32, spread: u32, color: Color) -> Result<Image> {
+        let (margin, w, h) = self.grown_size(blur_radius, spread, 0, 0)?;
+        let mut glow = Image::new(w, h);
+        self.shadow_mask(&mut glow, margin, margin, blur_radius, spread, color);
+
+        // Cut out the parts covered by the image
+        for y in 0..self.h as usize {
+            let start = (y + margin as usize) * glow.w as usize + margin as usize;
+            let row = &mut glow.data[start..start + self.w as usize];
+            for (pixel, src) in row.iter_mut().zip(self.data[y * self.w as usize..].iter()) {
+                let alpha = (pixel.data >> 24) * (255 - (src.data >> 24)) / 255;
+                pixel.data = (pixel.data & 0xFFFFFF) | (alpha << 24);
+            }
+        }
+
+        Ok(glow)
+    }
+
+    /// Margin of `blur_radius + spread` pixels, and the size of the image grown by the margin
+    /// on every side plus `extra_w` and `extra_h`
+    fn grown_size(&self, blur_radius: u32, spread: u32, extra_w: u32, extra_h: u32) -> Result<(u32, u32, u32)> {
+        let margin = blur_radius.checked_add(spread);
+        let grow = |len: u32, extra: u32| margin?.checked_mul(2)?.checked_add(len)?.checked_add(extra);
+        match (margin, grow(self.w, extra_w), grow(self.h, extra_h)) {
+            (Some(margin), Some(w), Some(h)) => {
+                ::pixel_count(w, h)?;
+                Ok((margin, w, h))
+            },
+            _ => Err(Error::SizeOverflow { width: self.w, height: self.h }),
+        }
+    }
+
+    /// Fill `canvas` with `color`, using the alpha of this image placed at x, y as a mask
+    fn shadow_mask(&self, canvas: &mut Image, x: u32, y: u32, blur_radius: u32, spread: u32, color: Color) {
+        let (cw, ch) = (canvas.w as usize, canvas.h as usize);
+        let mut mask = vec![0u8; cw * ch];
+        for row in 0..self.h as usize {
+            let start = (y as usize + row) * cw + x as usize;
+            let src = &self.data[row * self.w as usize..(row + 1) * self.w as usize];
+            for (alpha, pixel) in mask[start..start + src.len()].iter_mut().zip(src) {
+                *alpha = (pixel.data >> 24) as u8;
+            }
+        }
+
+        dilate(&mut mask, cw, ch, spread as usize);
+
+        let rgb = color.data & 0xFFFFFF;
+        let color_alpha = color.data >> 24;
+        for (pixel, alpha) in canvas.data.iter_mut().zip(&mask) {
+            pixel.data = rgb | (*alpha as u32) << 24;
+        }
+
+        // Three box blurs with radii adding up to the blur radius spread the edge by exactly that much
+        for pass in 0..3 {
+            let radius = blur_radius / 3 + if pass < blur_radius % 3 { 1 } else { 0 };
+            ::blur::box_blur(&mut canvas.data, cw, cw as u32, ch as u32, false, radius, 1);
+        }
+
+        for pixel in canvas.data.iter_mut() {
+            let alpha = (pixel.data >> 24) * color_alpha / 255;
+            pixel.data = rgb | alpha << 24;
+        }
+    }
+}
+
+/// Grow the opaque parts of an alpha mask by `radius` pixels in every direction
+fn dilate(mask: &mut [u8], w: usize, h: usize, radius: usize) {
+    if radius == 0 {
+        return;
+    }
+
+    let mut line = Vec::with_capacity(cmp::max(w, h));
+    for row in mask.chunks_mut(w) {
+        line.clear();
+        line.extend_from_slice(row);
+        for (x, alpha) in row.iter_mut().enumerate() {
+            *alpha = line[x.saturating_sub(radius)..cmp::min(x + radius + 1, w)].iter().cloned().max().unwrap_or(0);
+        }
+    }
+
+    for x in 0..w {
+        line.clear();
+        line.extend((0..h).map(|y| mask[y * w + x]));
+        for y in 0..h {
+            mask[y * w + x] = line[y.saturating_sub(radius)..cmp::min(y + radius + 1, h)].iter().cloned().max().unwrap_or(0);
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use orbclient::Color;
+
+use adjust::LUMA;
+use Image;
+
+impl Image {
+    /// Replace the color of every pixel, keeping its alpha. The alpha of the
+    /// tint scales the alpha of the image. Meant for symbolic icons
+    pub fn tint(&mut self, color: Color) {
+        let rgb = color.data & 0xFFFFFF;
+        let tint_alpha = color.data >> 24;
+        self.with_straight(|image| {
+            for pixel in image.data.iter_mut() {
+                let alpha = (pixel.data >> 24) * tint_alpha / 255;
+                pixel.data = alpha << 24 | rgb;
+            }
+        });
+    }
+
+    /// Recolor the image with the hue and saturation of a color, keeping the luminance
+    /// of every pixel so that shading survives. Black and white are left unchanged,
+    /// and the alpha of the color scales the alpha of the image
+    pub fn colorize(&mut self, color: Color) {
+        let [cb, cg, cr, ca] = color.data.to_le_bytes();
+        let target = [cr as f32 / 255.0, cg as f32 / 255.0, cb as f32 / 255.0];
+        let target_luma = luma(target);
+
+        self.with_straight(|image| {
+            for pixel in image.data.iter_mut() {
+                let [b, g, r, a] = pixel.data.to_le_bytes();
+                let l = luma([r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0]);
+                // Darken towards black or lighten towards white to reach the pixel luminance
+                let channel = |c: f32| {
+                    let c = if l <= target_luma {
+                        if target_luma > 0.0 { c * l / target_luma } else { l }
+                    } else {
+                        c + (1.0 - c) * (l - target_luma) / (1.0 - target_luma)
+                    };
+                    (c * 255.0 + 0.5).clamp(0.0, 255.0) as u8
+                };
+                let alpha = (a as u32 * ca as u32 / 255) as u8;
+                pixel.data = u32::from_le_bytes([channel(target[2]), channel(target[1]), channel(target[0]), alpha]);
+            }
+        });
+    }
+}
+
+fn luma(rgb: [f32; 3]) -> f32 {
+    LUMA[0] * rgb[0] + LUMA[1] * rgb[1] + LUMA[2] * rgb[2]
+}
+
+/// Keeps recolored copies of an image, so that drawing it in the same colors
+/// again does not recolor it again
+pub struct TintCache {
+    image: Image,
+    tinted: HashMap<(u32, bool), Image>,
+}
+
+impl TintCache {
+    /// Create a cache of recolored copies of an image
+    pub fn new(image: Image) -> Self {
+        TintCache {
+            image,
+            tinted: HashMap::new(),
+        }
+    }
+
+    /// Get the original image
+    pub fn image(&self) -> &Image {
+        &self.image
+    }
+
+    /// Get a copy of the image recolored with `Image::tint`
+    pub fn tinted(&mut self, color: Color) -> &Image {
+        let image = &self.image;
+        self.tinted.entry((color.data, false)).or_insert_with(|| {
+            let mut tinted = image.clone();
+            tinted.tint(color);
+            tinted
+        })
+    }
+
+    /// Get a copy of the image recolored with `Image::colorize`
+    pub fn colorized(&mut self, color: Color) -> &Image {
+        let image = &self.image;
+        self.tinted.entry((color.data, true)).or_insert_with(|| {
+            let mut colorized = image.clone();
+            colorized.colorize(color);
+            colorized
+        })
+    }
+
+    /// Drop all recolored copies, for example after a theme change
+    pub fn clear(&mut self) {
+        self.tinted.clear();
+    }
+}
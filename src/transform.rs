@@ -0,0 +1,117 @@
+use Image;
+
+impl Image {
+    /// Get a copy of the image mirrored left to right
+    pub fn flip_horizontal(&self) -> Self {
+        let mut image = self.clone();
+        image.flip_horizontal_in_place();
+        image
+    }
+
+    /// Mirror the image left to right
+    pub fn flip_horizontal_in_place(&mut self) {
+        if self.w == 0 {
+            return;
+        }
+        for row in self.data.chunks_mut(self.w as usize) {
+            row.reverse();
+        }
+    }
+
+    /// Get a copy of the image mirrored top to bottom
+    pub fn flip_vertical(&self) -> Self {
+        let mut image = self.clone();
+        image.flip_vertical_in_place();
+        image
+    }
+
+    /// Mirror the image top to bottom
+    pub fn flip_vertical_in_place(&mut self) {
+        let w = self.w as usize;
+        let h = self.h as usize;
+        for y in 0..h / 2 {
+            let (top, bottom) = self.data.split_at_mut((h - 1 - y) * w);
+            top[y * w..(y + 1) * w].swap_with_slice(&mut bottom[..w]);
+        }
+    }
+
+    /// Get a copy of the image rotated by 180 degrees
+    pub fn rotate180(&self) -> Self {
+        let mut image = self.clone();
+        image.rotate180_in_place();
+        image
+    }
+
+    /// Rotate the image by 180 degrees
+    pub fn rotate180_in_place(&mut self) {
+        self.data.reverse();
+    }
+
+    /// Get a copy of the image rotated by 90 degrees clockwise
+    pub fn rotate90(&self) -> Self {
+        let (w, h) = (self.w as usize, self.h as usize);
+        // Destination pixel (x, y) comes from source (y, h - 1 - x)
+        self.remap(|x, y| (h - 1 - x) * w + y)
+    }
+
+    /// Rotate the image by 90 degrees clockwise. Non-square images are reallocated
+    pub fn rotate90_in_place(&mut self) {
+        if self.w == self.h {
+            self.transpose_in_place();
+            self.flip_horizontal_in_place();
+        } else {
+            *self = self.rotate90();
+        }
+    }
+
+    /// Get a copy of the image rotated by 270 degrees clockwise
+    pub fn rotate270(&self) -> Self {
+        let w = self.w as usize;
+        // Destination pixel (x, y) comes from source (w - 1 - y, x)
+        self.remap(|x, y| x * w + (w - 1 - y))
+    }
+
+    /// Rotate the image by 270 degrees clockwise. Non-square images are reallocated
+    pub fn rotate270_in_place(&mut self) {
+        if self.w == self.h {
+            self.transpose_in_place();
+            self.flip_vertical_in_place();
+        } else {
+            *self = self.rotate270();
+        }
+    }
+
+    /// Get a copy of the image mirrored along its main diagonal, swapping rows and columns
+    pub fn transpose(&self) -> Self {
+        let w = self.w as usize;
+        self.remap(|x, y| x * w + y)
+    }
+
+    /// Mirror the image along its main diagonal. Non-square images are reallocated
+    pub fn transpose_in_place(&mut self) {
+        if self.w == self.h {
+            let n = self.w as usize;
+            for y in 0..n {
+                for x in y + 1..n {
+                    self.data.swap(y * n + x, x * n + y);
+                }
+            }
+        } else {
+            *self = self.transpose();
+        }
+    }
+
+    /// Build an image with width and height swapped, where each destination pixel
+    /// is read from the source index returned by `source`
+    fn remap<F: Fn(usize, usize) -> usize>(&self, source: F) -> Self {
+        let (w, h) = (self.h, self.w);
+        let mut data = Vec::with_capacity(self.data.len());
+        for y in 0..h as usize {
+            for x in 0..w as usize {
+                data.push(self.data[source(x, y)]);
+            }
+        }
+
+        self.with_data(w, h, data.into_boxed_slice())
+    }
+}
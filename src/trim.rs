@@ -0,0 +1,68 @@
+use orbclient::Color;
+
+use {Image, ImageRoi};
+
+impl Image {
+    /// Get the smallest rectangle, as (x, y, w, h), holding every pixel with alpha above
+    /// the threshold. Returns None if the image has no such pixel
+    pub fn content_bounds(&self, threshold: u8) -> Option<(u32, u32, u32, u32)> {
+        self.bounds_where(|color| (color.data >> 24) as u8 > threshold)
+    }
+
+    /// Get the smallest rectangle, as (x, y, w, h), holding every pixel that differs from
+    /// a background color by more than the threshold in any channel, alpha included.
+    /// Returns None if the whole image is background
+    pub fn content_bounds_on(&self, background: Color, threshold: u8) -> Option<(u32, u32, u32, u32)> {
+        let background = background.data.to_le_bytes();
+        self.bounds_where(|color| {
+            color.data.to_le_bytes().iter().zip(background.iter()).any(|(a, b)| a.abs_diff(*b) > threshold)
+        })
+    }
+
+    /// Get a copy of the image without its fully transparent borders
+    pub fn trim(&self) -> Image {
+        self.trim_roi().to_image()
+    }
+
+    /// Get the piece of the image inside its fully transparent borders. Its position
+    /// tells how far the visible content is from the corner of the image
+    pub fn trim_roi<'a>(&'a self) -> ImageRoi<'a> {
+        let (x, y, w, h) = self.content_bounds(0).unwrap_or((0, 0, 0, 0));
+        self.roi(x, y, w, h)
+    }
+
+    /// Get a copy of the image without its borders of a uniform background color, allowing
+    /// each channel to differ from it by up to the threshold
+    pub fn trim_on(&self, background: Color, threshold: u8) -> Image {
+        self.trim_roi_on(background, threshold).to_image()
+    }
+
+    /// Get the piece of the image inside its borders of a uniform background color, like `trim_on`
+    pub fn trim_roi_on<'a>(&'a self, background: Color, threshold: u8) -> ImageRoi<'a> {
+        let (x, y, w, h) = self.content_bounds_on(background, threshold).unwrap_or((0, 0, 0, 0));
+        self.roi(x, y, w, h)
+    }
+
+    fn bounds_where<F: Fn(Color) -> bool>(&self, content: F) -> Option<(u32, u32, u32, u32)> {
+        let w = self.w as usize;
+        if w == 0 {
+            return None;
+        }
+        let rows: Vec<&[Color]> = self.data.chunks(w).collect();
+        let has_content = |row: &&[Color]| row.iter().any(|&color| content(color));
+
+        let top = rows.iter().position(&has_content)?;
+        let bottom = rows.iter().rposition(&has_content)?;
+        let (mut left, mut right) = (w, 0);
+        for row in &rows[top..=bottom] {
+            if let Some(x) = row[..left].iter().position(|&color| content(color)) {
+                left = x;
+            }
+            if let Some(x) = row[right..].iter().rposition(|&color| content(color)) {
+                right += x;
+            }
+        }
+
+        Some((left as u32, top as u32, (right - left + 1) as u32, (bottom - top + 1) as u32))
+    }
+}
@@ -0,0 +1,54 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::Image;
+
+fn adjusted<F: FnOnce(&mut Image)>(color: Color, f: F) -> u32 {
+    let mut image = Image::from_color(1, 1, color);
+    f(&mut image);
+    image.data()[0].data
+}
+
+#[test]
+fn brighten_and_contrast() {
+    assert_eq!(adjusted(Color::rgba(100, 0, 250, 7), |i| i.brighten(0.2)), 0x079733FF);
+    assert_eq!(adjusted(Color::rgb(0, 128, 255), |i| i.contrast(0.0)), 0xFF808080);
+    assert_eq!(adjusted(Color::rgb(10, 128, 245), |i| i.contrast(1.0)), 0xFF0A80F5);
+}
+
+#[test]
+fn gamma() {
+    assert_eq!(adjusted(Color::rgb(0, 64, 255), |i| i.gamma(1.0)), 0xFF0040FF);
+    assert_eq!(adjusted(Color::rgb(0, 64, 255), |i| i.gamma(2.0)), 0xFF0080FF);
+}
+
+#[test]
+fn hue_and_saturation() {
+    assert_eq!(adjusted(Color::rgb(200, 100, 50), |i| i.hue_rotate(0.0)), 0xFFC86432);
+    assert_eq!(adjusted(Color::rgb(200, 100, 50), |i| i.hue_rotate(360.0)), 0xFFC86432);
+    assert_eq!(adjusted(Color::rgb(200, 100, 50), |i| i.saturate(1.0)), 0xFFC86432);
+    // Grays have no hue to rotate
+    assert_eq!(adjusted(Color::rgb(90, 90, 90), |i| i.hue_rotate(123.0)), 0xFF5A5A5A);
+}
+
+#[test]
+fn invert_grayscale_sepia() {
+    assert_eq!(adjusted(Color::rgba(255, 0, 16, 40), |i| i.invert()), 0x2800FFEF);
+    assert_eq!(adjusted(Color::rgb(255, 255, 255), |i| i.grayscale()), 0xFFFFFFFF);
+    let gray = adjusted(Color::rgb(255, 0, 0), |i| i.grayscale());
+    assert_eq!(gray & 0xFF, (gray >> 8) & 0xFF);
+    assert_eq!(gray & 0xFF, 54);
+    assert_eq!(adjusted(Color::rgb(0, 0, 0), |i| i.sepia()), 0xFF000000);
+}
+
+#[test]
+fn color_matrix_alpha_row() {
+    let half_alpha = [
+        [1.0, 0.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 0.0, 0.5, 0.0],
+    ];
+    assert_eq!(adjusted(Color::rgb(1, 2, 3), |i| i.color_matrix(&half_alpha)), 0x80010203);
+}
@@ -0,0 +1,62 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::{Affine, Image, Interpolation};
+
+fn numbered(w: u32, h: u32) -> Image {
+    let data: Vec<Color> = (0..w * h).map(|i| Color::rgb(i as u8 + 1, 0, 0)).collect();
+    Image::from_data(w, h, data.into_boxed_slice()).unwrap()
+}
+
+fn raw(image: &Image) -> Vec<u32> {
+    image.data().iter().map(|c| c.data).collect()
+}
+
+#[test]
+fn compose_and_invert() {
+    let t = Affine::scale(2.0, 3.0).then(&Affine::translate(1.0, -1.0));
+    assert_eq!(t.apply(1.0, 1.0), (3.0, 2.0));
+
+    let inverse = t.invert().unwrap();
+    let (x, y) = inverse.apply(3.0, 2.0);
+    assert!((x - 1.0).abs() < 1e-9 && (y - 1.0).abs() < 1e-9);
+
+    assert!(Affine::scale(0.0, 1.0).invert().is_none());
+}
+
+#[test]
+fn identity_and_translate() {
+    let image = numbered(3, 2);
+    for &interpolation in &[Interpolation::Nearest, Interpolation::Bilinear, Interpolation::Bicubic] {
+        let same = image.transform(&Affine::identity(), 3, 2, interpolation);
+        assert_eq!(raw(&same), raw(&image));
+    }
+
+    let moved = image.transform(&Affine::translate(1.0, 0.0), 3, 2, Interpolation::Nearest);
+    assert_eq!(raw(&moved), vec![0, 0xFF010000, 0xFF020000, 0, 0xFF040000, 0xFF050000]);
+}
+
+#[test]
+fn rotate_right_angles() {
+    let image = numbered(3, 2);
+    let rotated = image.rotate(90.0, Interpolation::Bilinear);
+    assert_eq!((rotated.width(), rotated.height()), (2, 3));
+    assert_eq!(raw(&rotated), raw(&image.rotate90()));
+
+    let rotated = image.rotate(180.0, Interpolation::Nearest);
+    assert_eq!(raw(&rotated), raw(&image.rotate180()));
+}
+
+#[test]
+fn rotate_expands_with_soft_edges() {
+    let image = Image::from_color(10, 10, Color::rgb(255, 255, 255));
+    let rotated = image.rotate(45.0, Interpolation::Bilinear);
+    assert_eq!((rotated.width(), rotated.height()), (15, 15));
+
+    // Corners are transparent, the middle opaque, and colors are not darkened at the edge
+    assert_eq!(rotated.data()[0].data, 0);
+    assert_eq!(rotated.data()[7 * 15 + 7].data, 0xFFFFFFFF);
+    let partial = rotated.data().iter().find(|c| c.a() > 0 && c.a() < 255).unwrap();
+    assert_eq!(partial.data & 0xFFFFFF, 0xFFFFFF);
+}
@@ -0,0 +1,80 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::{AlphaMode, CompositeOp, Image, ResizeType};
+
+fn raw(image: &Image) -> Vec<u32> {
+    image.data().iter().map(|c| c.data).collect()
+}
+
+#[test]
+fn premultiply_round_trip() {
+    let mut image = Image::from_color(1, 1, Color::rgba(200, 100, 50, 128));
+    assert_eq!(image.alpha_mode(), AlphaMode::Straight);
+
+    image.premultiply();
+    assert_eq!(image.alpha_mode(), AlphaMode::Premultiplied);
+    assert_eq!(raw(&image), vec![0x80643219]);
+
+    // Converting twice does nothing
+    image.premultiply();
+    assert_eq!(raw(&image), vec![0x80643219]);
+
+    image.unpremultiply();
+    assert_eq!(image.alpha_mode(), AlphaMode::Straight);
+    // Storing premultiplied bytes loses some precision
+    assert_eq!(raw(&image), vec![0x80C76432]);
+}
+
+#[test]
+fn resize_without_dark_fringe() {
+    // A white pixel next to a transparent black one
+    let data = vec![Color::rgb(255, 255, 255), Color::rgba(0, 0, 0, 0)];
+    let image = Image::from_data(2, 1, data.into_boxed_slice()).unwrap();
+    let resized = image.resize(1, 1, ResizeType::Triangle).unwrap();
+
+    let [b, g, r, a] = resized.data()[0].data.to_le_bytes();
+    assert!(a > 0 && a < 255);
+    assert_eq!((r, g, b), (255, 255, 255));
+}
+
+#[test]
+fn resize_keeps_alpha_mode() {
+    let mut image = Image::from_color(4, 4, Color::rgba(255, 0, 0, 128));
+    image.premultiply();
+    let resized = image.resize(2, 2, ResizeType::Lanczos3).unwrap();
+    assert_eq!(resized.alpha_mode(), AlphaMode::Premultiplied);
+    assert_eq!(raw(&resized), vec![0x80800000; 4]);
+}
+
+#[test]
+fn composite_matches_straight() {
+    let src = Image::from_color(1, 1, Color::rgba(0, 0, 255, 100));
+    let mut straight = Image::from_color(1, 1, Color::rgba(255, 0, 0, 200));
+    straight.composite(&src, 0, 0, CompositeOp::Over, 1.0);
+
+    let mut premultiplied_src = src.clone();
+    premultiplied_src.premultiply();
+    let mut premultiplied = Image::from_color(1, 1, Color::rgba(255, 0, 0, 200));
+    premultiplied.premultiply();
+    premultiplied.composite(&premultiplied_src, 0, 0, CompositeOp::Over, 1.0);
+    premultiplied.unpremultiply();
+
+    for (a, b) in straight.data()[0].data.to_le_bytes().iter().zip(&premultiplied.data()[0].data.to_le_bytes()) {
+        assert!((*a as i32 - *b as i32).abs() <= 1);
+    }
+}
+
+#[test]
+fn blur_premultiplied() {
+    let mut image = Image::from_color(3, 1, Color::rgba(0, 0, 0, 0));
+    image.data_mut()[1] = Color::rgb(255, 255, 255);
+    let mut premultiplied = image.clone();
+    premultiplied.premultiply();
+
+    image.box_blur_passes(1, 1);
+    premultiplied.box_blur_passes(1, 1);
+    premultiplied.unpremultiply();
+    assert_eq!(raw(&premultiplied), raw(&image));
+}
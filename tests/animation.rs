@@ -0,0 +1,245 @@
+extern crate gif;
+extern crate orbclient;
+extern crate orbimage;
+
+mod common;
+
+use std::borrow::Cow;
+use std::time::Duration;
+
+use gif::SetParameter;
+use orbclient::{Color, Renderer};
+use orbimage::{Animation, BlendOp, DisposeOp, Image, LoopCount};
+
+const CLEAR: u32 = 0;
+const RED: u32 = 0xFFFF0000;
+const GREEN: u32 = 0xFF00FF00;
+const BLUE: u32 = 0xFF0000FF;
+
+fn raw(image: &Image) -> Vec<u32> {
+    image.data().iter().map(|c| c.data).collect()
+}
+
+fn frames(animation: &Animation) -> Vec<Vec<u32>> {
+    animation.frames().iter().map(|frame| raw(frame.image())).collect()
+}
+
+/// A GIF frame of one palette index, with index 3 transparent
+fn gif_frame(left: u16, top: u16, width: u16, height: u16, index: u8, dispose: gif::DisposalMethod) -> gif::Frame<'static> {
+    gif::Frame {
+        left,
+        top,
+        width,
+        height,
+        delay: 5,
+        dispose,
+        transparent: Some(3),
+        buffer: Cow::Owned(vec![index; width as usize * height as usize]),
+        ..gif::Frame::default()
+    }
+}
+
+fn gif_bytes(repeat: Option<gif::Repeat>, frames: &[gif::Frame]) -> Vec<u8> {
+    let palette = [255, 0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0];
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = gif::Encoder::new(&mut bytes, 2, 2, &palette).unwrap();
+        if let Some(repeat) = repeat {
+            encoder.set(repeat).unwrap();
+        }
+        for frame in frames {
+            encoder.write_frame(frame).unwrap();
+        }
+    }
+    bytes
+}
+
+#[test]
+fn gif_frames_are_composited_with_disposal() {
+    let bytes = gif_bytes(Some(gif::Repeat::Finite(2)), &[
+        gif_frame(0, 0, 2, 2, 0, gif::DisposalMethod::Keep),
+        gif_frame(1, 1, 1, 1, 1, gif::DisposalMethod::Background),
+        gif_frame(0, 0, 1, 1, 2, gif::DisposalMethod::Previous),
+        gif_frame(1, 0, 1, 1, 3, gif::DisposalMethod::Keep),
+    ]);
+    let animation = Animation::from_bytes(&bytes).unwrap();
+
+    assert_eq!((animation.width(), animation.height()), (2, 2));
+    assert_eq!(frames(&animation), vec![
+        vec![RED, RED, RED, RED],
+        vec![RED, RED, RED, GREEN],
+        // The background disposal of the previous frame cleared its region
+        vec![BLUE, RED, RED, CLEAR],
+        // The previous disposal restored the canvas, and the transparent pixel keeps it
+        vec![RED, RED, RED, CLEAR],
+    ]);
+    assert_eq!(animation.frames()[1].dispose(), DisposeOp::Background);
+    assert_eq!(animation.frames()[0].blend(), BlendOp::Over);
+    assert_eq!(animation.frames()[0].delay(), Duration::from_millis(50));
+    assert_eq!(animation.duration(), Duration::from_millis(200));
+    // The extension counts repeats after the first play
+    assert_eq!(animation.loop_count(), LoopCount::Finite(3));
+}
+
+#[test]
+fn gif_loop_count() {
+    let frame = gif_frame(0, 0, 2, 2, 0, gif::DisposalMethod::Keep);
+    let infinite = gif_bytes(Some(gif::Repeat::Infinite), std::slice::from_ref(&frame));
+    assert_eq!(Animation::from_bytes(&infinite).unwrap().loop_count(), LoopCount::Infinite);
+
+    // An extension lookalike inside a comment is not a loop count
+    let mut once = gif_bytes(None, &[frame]);
+    let trailer = once.pop().unwrap();
+    once.extend_from_slice(b"\x21\xFE\x12\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00");
+    once.push(trailer);
+    assert_eq!(Animation::from_bytes(&once).unwrap().loop_count(), LoopCount::Finite(1));
+}
+
+/// Encode an image as PNG, returning its IHDR and compressed image data
+fn png_parts(image: &Image) -> (Vec<u8>, Vec<u8>) {
+    let mut png = Vec::new();
+    image.encode_png(&mut png).unwrap();
+    (common::chunk_data(&png, b"IHDR"), common::chunk_data(&png, b"IDAT"))
+}
+
+struct ApngFrame {
+    image: Image,
+    x: u32,
+    y: u32,
+    delay: (u16, u16),
+    dispose: u8,
+    blend: u8,
+}
+
+fn apng_frame(color: u32, w: u32, h: u32, x: u32, y: u32, dispose: u8, blend: u8) -> ApngFrame {
+    ApngFrame {
+        image: Image::from_color(w, h, Color { data: color }),
+        x,
+        y,
+        delay: (1, 0),
+        dispose,
+        blend,
+    }
+}
+
+fn apng_bytes(width: u32, height: u32, plays: u32, frames: &[ApngFrame]) -> Vec<u8> {
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    let (mut ihdr, _) = png_parts(&frames[0].image);
+    ihdr[0..4].copy_from_slice(&width.to_be_bytes());
+    ihdr[4..8].copy_from_slice(&height.to_be_bytes());
+    common::write_chunk(&mut png, b"IHDR", &ihdr);
+
+    let mut actl = (frames.len() as u32).to_be_bytes().to_vec();
+    actl.extend_from_slice(&plays.to_be_bytes());
+    common::write_chunk(&mut png, b"acTL", &actl);
+
+    let mut sequence = 0u32;
+    for (i, frame) in frames.iter().enumerate() {
+        let mut fctl = sequence.to_be_bytes().to_vec();
+        for value in &[frame.image.width(), frame.image.height(), frame.x, frame.y] {
+            fctl.extend_from_slice(&value.to_be_bytes());
+        }
+        fctl.extend_from_slice(&frame.delay.0.to_be_bytes());
+        fctl.extend_from_slice(&frame.delay.1.to_be_bytes());
+        fctl.push(frame.dispose);
+        fctl.push(frame.blend);
+        common::write_chunk(&mut png, b"fcTL", &fctl);
+        sequence += 1;
+
+        let (_, idat) = png_parts(&frame.image);
+        if i == 0 {
+            common::write_chunk(&mut png, b"IDAT", &idat);
+        } else {
+            let mut fdat = sequence.to_be_bytes().to_vec();
+            fdat.extend_from_slice(&idat);
+            common::write_chunk(&mut png, b"fdAT", &fdat);
+            sequence += 1;
+        }
+    }
+    common::write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+#[test]
+fn apng_frames_are_composited_with_blending_and_disposal() {
+    let mut over = apng_frame(0x800000FF, 1, 1, 1, 0, 2, 1);
+    over.delay = (3, 4);
+    let bytes = apng_bytes(2, 2, 2, &[
+        apng_frame(RED, 2, 2, 0, 0, 0, 0),
+        over,
+        apng_frame(0x0000FF00, 1, 1, 0, 1, 1, 0),
+        // Outside the canvas, so nothing is drawn
+        apng_frame(BLUE, 1, 1, 5, 0, 0, 1),
+    ]);
+    let animation = Animation::from_bytes(&bytes).unwrap();
+
+    let frames = frames(&animation);
+    assert_eq!(frames[0], vec![RED; 4]);
+    // Half transparent blue blended over red
+    assert_eq!(frames[1][1], 0xFF7F0080);
+    assert_eq!(frames[1][0], RED);
+    // Source blending replaces the pixel, alpha included
+    assert_eq!(frames[2], vec![RED, RED, 0x0000FF00, RED]);
+    assert_eq!(frames[3], vec![RED, RED, CLEAR, RED]);
+
+    assert_eq!(animation.frames()[0].delay(), Duration::from_millis(10));
+    assert_eq!(animation.frames()[1].delay(), Duration::from_millis(750));
+    assert_eq!(animation.frames()[1].blend(), BlendOp::Over);
+    assert_eq!(animation.frames()[1].dispose(), DisposeOp::Previous);
+    assert_eq!(animation.loop_count(), LoopCount::Finite(2));
+}
+
+#[test]
+fn apng_first_frame_previous_disposal_clears() {
+    let bytes = apng_bytes(2, 1, 0, &[
+        apng_frame(RED, 2, 1, 0, 0, 2, 0),
+        apng_frame(GREEN, 1, 1, 0, 0, 0, 1),
+    ]);
+    let animation = Animation::from_bytes(&bytes).unwrap();
+
+    assert_eq!(animation.frames()[0].dispose(), DisposeOp::Background);
+    assert_eq!(frames(&animation), vec![vec![RED, RED], vec![GREEN, CLEAR]]);
+    assert_eq!(animation.loop_count(), LoopCount::Infinite);
+}
+
+#[test]
+fn still_images_load_as_one_frame() {
+    let image = Image::from_color(3, 2, Color { data: BLUE });
+    let mut png = Vec::new();
+    image.encode_png(&mut png).unwrap();
+    let mut bmp = Vec::new();
+    image.encode_bmp(&mut bmp).unwrap();
+
+    for bytes in &[png, bmp] {
+        let animation = Animation::from_bytes(bytes).unwrap();
+        assert_eq!((animation.width(), animation.height()), (3, 2));
+        assert_eq!(frames(&animation), vec![raw(&image)]);
+        assert_eq!(animation.loop_count(), LoopCount::Finite(1));
+        assert_eq!(animation.duration(), Duration::from_secs(0));
+    }
+}
+
+/// Uncompressed 32 bit TGA, stored top to bottom
+fn tga_bytes(width: u16, height: u16, pixels: &[u32]) -> Vec<u8> {
+    let mut tga = vec![0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    tga.extend_from_slice(&width.to_le_bytes());
+    tga.extend_from_slice(&height.to_le_bytes());
+    tga.extend_from_slice(&[32, 0x28]);
+    for pixel in pixels {
+        tga.extend_from_slice(&pixel.to_le_bytes());
+    }
+    tga
+}
+
+#[test]
+fn from_path_recognizes_formats_by_extension() {
+    let pixels = [RED, GREEN, BLUE, 0x80FFFFFF];
+    let path = std::env::temp_dir().join(format!("orbimage-animation-{}.tga", std::process::id()));
+    std::fs::write(&path, tga_bytes(2, 2, &pixels)).unwrap();
+    let animation = Animation::from_path(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    let animation = animation.unwrap();
+    assert_eq!((animation.width(), animation.height()), (2, 2));
+    assert_eq!(frames(&animation), vec![pixels.to_vec()]);
+}
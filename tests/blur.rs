@@ -0,0 +1,97 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::Image;
+
+fn raw(image: &Image) -> Vec<u32> {
+    image.data().iter().map(|c| c.data).collect()
+}
+
+/// A transparent image with a single opaque white pixel in the middle
+fn dot(size: u32) -> Image {
+    let mut image = Image::from_color(size, size, Color::rgba(0, 0, 0, 0));
+    let center = (size / 2 * size + size / 2) as usize;
+    image.data_mut()[center] = Color::rgb(255, 255, 255);
+    image
+}
+
+#[test]
+fn gaussian_spreads_alpha_not_color() {
+    let mut image = dot(9);
+    image.gaussian_blur(1.0);
+
+    let data = raw(&image);
+    let center = data[40];
+    let neighbour = data[41];
+    assert!(center >> 24 < 255);
+    assert!(neighbour >> 24 > 0 && neighbour >> 24 < center >> 24);
+    // Transparent neighbours must not darken the color
+    assert_eq!(neighbour & 0xFFFFFF, 0xFFFFFF);
+    // Symmetric around the center
+    assert_eq!(data[39], neighbour);
+    assert_eq!(data[31], neighbour);
+    assert_eq!(data[49], neighbour);
+}
+
+#[test]
+fn gaussian_uniform_unchanged() {
+    let mut image = Image::from_color(5, 4, Color::rgba(10, 20, 30, 200));
+    let before = raw(&image);
+    image.gaussian_blur(2.5);
+    assert_eq!(raw(&image), before);
+}
+
+#[test]
+fn box_blur_passes_averages() {
+    let mut image = Image::from_color(3, 1, Color::rgb(0, 0, 0));
+    image.data_mut()[1] = Color::rgb(255, 255, 255);
+    image.box_blur_passes(1, 1);
+
+    assert_eq!(raw(&image), vec![0xFF555555; 3]);
+}
+
+#[test]
+fn zero_radius_is_noop() {
+    let mut image = dot(5);
+    let before = raw(&image);
+    image.box_blur_passes(0, 3);
+    image.gaussian_blur(0.0);
+    assert_eq!(raw(&image), before);
+}
+
+#[test]
+fn non_finite_sigma_is_noop() {
+    let mut image = dot(5);
+    let before = raw(&image);
+    image.gaussian_blur(f32::INFINITY);
+    image.gaussian_blur(f32::NEG_INFINITY);
+    image.gaussian_blur(f32::NAN);
+    assert_eq!(raw(&image), before);
+}
+
+#[test]
+fn huge_sigma_spreads_evenly() {
+    let mut image = dot(5);
+    image.gaussian_blur(1.0e30);
+
+    // The kernel is nearly flat, so every pixel gets the same share
+    let alphas: Vec<u32> = raw(&image).iter().map(|c| c >> 24).collect();
+    assert!(alphas[0] > 0);
+    assert!(alphas.iter().all(|&a| a == alphas[0]), "{:?}", alphas);
+}
+
+#[test]
+fn roi_blur_leaves_outside() {
+    let mut image = Image::from_color(4, 4, Color::rgb(0, 0, 0));
+    image.data_mut()[5] = Color::rgb(255, 255, 255);
+    image.data_mut()[0] = Color::rgb(255, 255, 255);
+    image.roi_mut(1, 1, 2, 2).box_blur_passes(1, 1);
+
+    let data = raw(&image);
+    assert_eq!(data[0], 0xFFFFFFFF);
+    assert_eq!(data[3], 0xFF000000);
+    assert_eq!(data[15], 0xFF000000);
+    assert!(data[5] & 0xFF > 0 && data[5] & 0xFF < 255);
+    assert!(data[10] & 0xFF > 0);
+}
@@ -0,0 +1,45 @@
+//! PNG fixtures shared by the integration tests
+#![allow(dead_code)]
+
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Append a PNG chunk with its length and CRC
+pub fn write_chunk(png: &mut Vec<u8>, kind: &[u8], data: &[u8]) {
+    let mut body = kind.to_vec();
+    body.extend_from_slice(data);
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(&body);
+    png.extend_from_slice(&crc32(&body).to_be_bytes());
+}
+
+/// Insert a chunk into a PNG file right after its IHDR chunk
+pub fn insert_chunk(png: &mut Vec<u8>, kind: &[u8], data: &[u8]) {
+    let mut chunk = Vec::new();
+    write_chunk(&mut chunk, kind, data);
+    // Signature and IHDR chunk
+    let end = 8 + 25;
+    png.splice(end..end, chunk);
+}
+
+/// Concatenated contents of every chunk of a kind in a PNG file
+pub fn chunk_data(png: &[u8], kind: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut rest = &png[8..];
+    while !rest.is_empty() {
+        let len = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        if &rest[4..8] == kind {
+            data.extend_from_slice(&rest[8..8 + len]);
+        }
+        rest = &rest[12 + len..];
+    }
+    data
+}
@@ -0,0 +1,78 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::{CompositeOp, Image};
+
+fn raw(image: &Image) -> Vec<u32> {
+    image.data().iter().map(|c| c.data).collect()
+}
+
+#[test]
+fn over_opaque_replaces() {
+    let mut dst = Image::from_color(3, 3, Color::rgb(255, 0, 0));
+    let src = Image::from_color(2, 2, Color::rgb(0, 0, 255));
+    dst.composite(&src, 1, 1, CompositeOp::Over, 1.0);
+
+    assert_eq!(raw(&dst), vec![
+        0xFFFF0000, 0xFFFF0000, 0xFFFF0000,
+        0xFFFF0000, 0xFF0000FF, 0xFF0000FF,
+        0xFFFF0000, 0xFF0000FF, 0xFF0000FF,
+    ]);
+}
+
+#[test]
+fn over_with_opacity() {
+    let mut dst = Image::from_color(1, 1, Color::rgb(0, 0, 0));
+    let src = Image::from_color(1, 1, Color::rgb(255, 255, 255));
+    dst.composite(&src, 0, 0, CompositeOp::Over, 0.5);
+
+    assert_eq!(raw(&dst), vec![0xFF808080]);
+}
+
+#[test]
+fn clipped_negative_offset() {
+    let mut dst = Image::from_color(2, 2, Color::rgba(0, 0, 0, 0));
+    let src = Image::from_color(2, 2, Color::rgb(0, 255, 0));
+    dst.composite(&src, -1, -1, CompositeOp::Over, 1.0);
+
+    assert_eq!(raw(&dst), vec![0xFF00FF00, 0, 0, 0]);
+}
+
+#[test]
+fn in_and_out_use_destination_alpha() {
+    let src = Image::from_color(1, 1, Color::rgb(0, 0, 255));
+
+    let mut dst = Image::from_color(1, 1, Color::rgba(255, 0, 0, 0));
+    dst.composite(&src, 0, 0, CompositeOp::In, 1.0);
+    assert_eq!(raw(&dst), vec![0]);
+
+    let mut dst = Image::from_color(1, 1, Color::rgba(255, 0, 0, 0));
+    dst.composite(&src, 0, 0, CompositeOp::Out, 1.0);
+    assert_eq!(raw(&dst), vec![0xFF0000FF]);
+}
+
+#[test]
+fn xor_opaque_clears() {
+    let mut dst = Image::from_color(1, 1, Color::rgb(255, 0, 0));
+    let src = Image::from_color(1, 1, Color::rgb(0, 0, 255));
+    dst.composite(&src, 0, 0, CompositeOp::Xor, 1.0);
+
+    assert_eq!(raw(&dst), vec![0]);
+}
+
+#[test]
+fn blend_modes() {
+    let check = |op, expected| {
+        let mut dst = Image::from_color(1, 1, Color::rgb(255, 128, 0));
+        let src = Image::from_color(1, 1, Color::rgb(128, 128, 128));
+        dst.composite(&src, 0, 0, op, 1.0);
+        assert_eq!(raw(&dst), vec![expected], "{:?}", op);
+    };
+
+    check(CompositeOp::Multiply, 0xFF804000);
+    check(CompositeOp::Screen, 0xFFFFC080);
+    check(CompositeOp::Darken, 0xFF808000);
+    check(CompositeOp::Lighten, 0xFFFF8080);
+    check(CompositeOp::Difference, 0xFF7F0080);
+}
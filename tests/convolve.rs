@@ -0,0 +1,64 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::{EdgeMode, Image, Kernel};
+
+fn raw(image: &Image) -> Vec<u32> {
+    image.data().iter().map(|c| c.data).collect()
+}
+
+fn gray(values: &[u8]) -> Image {
+    let data = values.iter().map(|&v| Color::rgb(v, v, v)).collect();
+    Image::from_data(values.len() as u32, 1, data).unwrap()
+}
+
+fn shift_left() -> Kernel {
+    Kernel::new(3, 1, vec![0.0, 0.0, 1.0]).unwrap()
+}
+
+#[test]
+fn invalid_kernels() {
+    assert!(Kernel::new(2, 3, vec![0.0; 6]).is_err());
+    assert!(Kernel::new(3, 3, vec![0.0; 8]).is_err());
+    assert!(Kernel::new(1, 1, vec![1.0]).is_ok());
+}
+
+#[test]
+fn edge_modes() {
+    let image = gray(&[10, 20, 30]);
+    let last = |edge| raw(&image.convolve(&shift_left(), edge))[2] & 0xFF;
+
+    assert_eq!(last(EdgeMode::Clamp), 30);
+    assert_eq!(last(EdgeMode::Wrap), 10);
+    assert_eq!(last(EdgeMode::Mirror), 20);
+    assert_eq!(last(EdgeMode::Transparent), 0);
+}
+
+#[test]
+fn alpha_unchanged() {
+    let image = Image::from_color(3, 3, Color::rgba(100, 100, 100, 50));
+    let result = image.convolve(&Kernel::laplacian(), EdgeMode::Clamp);
+    assert_eq!(raw(&result), vec![0x32000000; 9]);
+
+    let result = image.convolve(&Kernel::sharpen(), EdgeMode::Clamp);
+    assert_eq!(raw(&result), raw(&image));
+}
+
+#[test]
+fn bias() {
+    let image = Image::from_color(3, 3, Color::rgb(100, 100, 100));
+    let result = image.convolve(&Kernel::laplacian().with_bias(0.5), EdgeMode::Clamp);
+    assert_eq!(raw(&result)[4], 0xFF808080);
+}
+
+#[test]
+fn sobel_finds_edges() {
+    let image = gray(&[0, 0, 255, 255]);
+    let edges = image.sobel(EdgeMode::Clamp);
+    let data = raw(&edges);
+
+    assert_eq!(data[0], 0xFF000000);
+    assert!(data[1] & 0xFF > 0);
+    assert_eq!(data[3], 0xFF000000);
+}
@@ -0,0 +1,71 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::Image;
+
+/// A 5x4 image whose pixels are numbered 1 to 20 in reading order
+fn numbered() -> Image {
+    let data: Vec<Color> = (1..21).map(|i| Color { data: 0xFF000000 | i }).collect();
+    Image::from_data(5, 4, data.into_boxed_slice()).unwrap()
+}
+
+fn layout(image: &Image) -> (u32, u32, Vec<u32>) {
+    (image.width(), image.height(), image.data().iter().map(|c| c.data & 0xFFFFFF).collect())
+}
+
+fn crop_both(x: u32, y: u32, w: u32, h: u32) -> (u32, u32, Vec<u32>) {
+    let image = numbered();
+    let owned = image.crop(x, y, w, h);
+    let mut in_place = image.clone();
+    in_place.crop_in_place(x, y, w, h);
+    assert_eq!(layout(&owned), layout(&in_place));
+    layout(&owned)
+}
+
+#[test]
+fn crop_inside() {
+    assert_eq!(crop_both(1, 1, 3, 2), (3, 2, vec![7, 8, 9, 12, 13, 14]));
+    assert_eq!(crop_both(0, 0, 5, 4), layout(&numbered()));
+}
+
+#[test]
+fn crop_keeps_overlapping_rows_in_order() {
+    // Rows move left and up onto data that is still to be read
+    assert_eq!(crop_both(1, 0, 4, 4), (4, 4, vec![
+        2, 3, 4, 5,
+        7, 8, 9, 10,
+        12, 13, 14, 15,
+        17, 18, 19, 20,
+    ]));
+    assert_eq!(crop_both(0, 1, 5, 3).2, (6..21).collect::<Vec<_>>());
+}
+
+#[test]
+fn crop_clamps_to_bounds() {
+    assert_eq!(crop_both(3, 2, 10, 10), (2, 2, vec![14, 15, 19, 20]));
+    assert_eq!(crop_both(2, 1, u32::MAX, u32::MAX), (3, 3, vec![8, 9, 10, 13, 14, 15, 18, 19, 20]));
+    assert_eq!(crop_both(5, 0, 2, 2), (0, 2, vec![]));
+    assert_eq!(crop_both(9, 9, 2, 2), (0, 0, vec![]));
+}
+
+#[test]
+fn nested_roi_offsets_are_relative() {
+    let image = numbered();
+    let outer = image.roi(1, 1, 4, 3);
+    let inner = outer.roi(1, 1, 2, 5);
+    assert_eq!((inner.x(), inner.y(), inner.width(), inner.height()), (2, 2, 2, 2));
+    assert_eq!(layout(&inner.to_image()), (2, 2, vec![13, 14, 18, 19]));
+
+    // Clamped to the outer ROI even where the image would have room
+    let clipped = image.roi(0, 0, 2, 2).roi(1, 1, 3, 3);
+    assert_eq!((clipped.x(), clipped.y(), clipped.width(), clipped.height()), (1, 1, 1, 1));
+    let outside = image.roi(0, 0, 2, 2).roi(5, 5, 1, 1);
+    assert_eq!((outside.width(), outside.height()), (0, 0));
+}
+
+#[test]
+fn roi_to_image_matches_crop() {
+    let image = numbered();
+    assert_eq!(layout(&image.roi(2, 1, 2, 3).to_image()), layout(&image.crop(2, 1, 2, 3)));
+}
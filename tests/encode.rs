@@ -0,0 +1,87 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::Image;
+
+fn sample() -> Image {
+    let mut data = Vec::new();
+    for y in 0..6u8 {
+        for x in 0..8u8 {
+            data.push(Color::rgba(x * 30, y * 40, 255 - x * 20, 55 + y * 40));
+        }
+    }
+    Image::from_data(8, 6, data.into_boxed_slice()).unwrap()
+}
+
+fn raw(image: &Image) -> Vec<u32> {
+    image.data().iter().map(|c| c.data).collect()
+}
+
+#[test]
+fn png_round_trip() {
+    let image = sample();
+    let mut bytes = Vec::new();
+    image.encode_png(&mut bytes).unwrap();
+
+    let decoded = orbimage::parse_png(&bytes).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (8, 6));
+    assert_eq!(raw(&decoded), raw(&image));
+}
+
+#[test]
+fn bmp_round_trip() {
+    let image = sample();
+    let mut bytes = Vec::new();
+    image.encode_bmp(&mut bytes).unwrap();
+
+    let decoded = orbimage::parse_bmp(&bytes).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (8, 6));
+    assert_eq!(raw(&decoded), raw(&image));
+}
+
+#[test]
+fn jpeg_round_trip() {
+    let image = Image::from_color(16, 16, Color::rgb(200, 100, 50));
+    let mut bytes = Vec::new();
+    image.encode_jpeg(&mut bytes, 100).unwrap();
+
+    let decoded = orbimage::parse_jpg(&bytes).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (16, 16));
+    for color in decoded.data() {
+        assert_eq!(color.a(), 255);
+        assert!((color.r() as i32 - 200).abs() <= 4);
+        assert!((color.g() as i32 - 100).abs() <= 4);
+        assert!((color.b() as i32 - 50).abs() <= 4);
+    }
+}
+
+#[test]
+fn jpeg_too_wide() {
+    let image = Image::new(70_000, 1);
+    match image.encode_jpeg(Vec::new(), 90) {
+        Err(orbimage::Error::UnsupportedFormat(_)) => (),
+        other => panic!("unexpected result {:?}", other.err()),
+    }
+}
+
+#[test]
+fn save_and_load() {
+    let image = sample();
+    let path = std::env::temp_dir().join(format!("orbimage-save-{}.png", std::process::id()));
+    image.save(&path).unwrap();
+    let loaded = Image::from_path(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(raw(&loaded.unwrap()), raw(&image));
+}
+
+#[test]
+fn save_unknown_extension() {
+    let path = std::env::temp_dir().join(format!("orbimage-save-{}.xyz", std::process::id()));
+    let result = sample().save(&path);
+    let _ = std::fs::remove_file(&path);
+    match result {
+        Err(orbimage::Error::UnsupportedFormat(_)) => (),
+        other => panic!("unexpected result {:?}", other.err()),
+    }
+}
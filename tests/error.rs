@@ -0,0 +1,63 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use std::error::Error as StdError;
+
+use orbclient::Color;
+use orbimage::{Error, Image, ResizeType};
+
+#[test]
+fn from_data_with_wrong_length_is_dimension_mismatch() {
+    let data = vec![Color::rgb(0, 0, 0); 5].into_boxed_slice();
+    match Image::from_data(2, 3, data) {
+        Err(Error::DimensionMismatch { width: 2, height: 3, len: 5 }) => (),
+        other => panic!("expected DimensionMismatch, got {:?}", other.err()),
+    }
+}
+
+// The pixel count of any u32 size fits in a 64 bit usize
+#[cfg(target_pointer_width = "32")]
+#[test]
+fn from_data_too_large_is_size_overflow() {
+    match Image::from_data(u32::MAX, u32::MAX, Box::new([])) {
+        Err(Error::SizeOverflow { width: u32::MAX, height: u32::MAX }) => (),
+        other => panic!("expected SizeOverflow, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn resizing_empty_image_is_resize_error() {
+    match Image::new(0, 0).resize(4, 4, ResizeType::Point) {
+        Err(Error::Resize(_)) => (),
+        other => panic!("expected Resize, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn io_error_has_source() {
+    let err = Image::from_path("/nonexistent/orbimage/image.png").err().unwrap();
+    match err {
+        Error::Io(_) => assert!(err.source().is_some()),
+        other => panic!("expected Io, got {:?}", other),
+    }
+}
+
+#[test]
+fn decode_error_has_source() {
+    // PNG magic followed by a header chunk with a bad checksum
+    let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+    bytes.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 1, 8, 6, 0, 0, 0, 0xDE, 0xAD, 0xBE, 0xEF]);
+    let err = orbimage::parse_png(&bytes).err().unwrap();
+    match err {
+        Error::Decode(_) => assert!(err.source().is_some()),
+        other => panic!("expected Decode, got {:?}", other),
+    }
+}
+
+#[test]
+fn other_errors_have_no_source() {
+    let err = Error::SizeOverflow { width: 1, height: 1 };
+    assert!(err.source().is_none());
+    assert_eq!(err.to_string(), "image size 1x1 is too large");
+    assert!(Error::UnsupportedFormat("xyz".to_string()).source().is_none());
+}
@@ -0,0 +1,119 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::{exif_orientation, Image, LoadOptions, Orientation};
+
+/// 3x2 image with pixels numbered 1 to 6 in reading order
+fn numbered() -> Image {
+    let data: Vec<Color> = (1..7).map(|n| Color { data: n }).collect();
+    Image::from_data(3, 2, data.into_boxed_slice()).unwrap()
+}
+
+fn layout(image: &Image) -> (u32, u32, Vec<u32>) {
+    (image.width(), image.height(), image.data().iter().map(|c| c.data).collect())
+}
+
+#[test]
+fn orientation_round_trips_exif_values() {
+    for value in 1..9 {
+        assert_eq!(Orientation::from_exif(value).unwrap().to_exif(), value);
+    }
+    assert_eq!(Orientation::from_exif(0), None);
+    assert_eq!(Orientation::from_exif(9), None);
+}
+
+#[test]
+fn apply_orientation_displays_upright() {
+    let cases = [
+        (Orientation::Normal, (3, 2, vec![1, 2, 3, 4, 5, 6])),
+        (Orientation::FlipHorizontal, (3, 2, vec![3, 2, 1, 6, 5, 4])),
+        (Orientation::Rotate180, (3, 2, vec![6, 5, 4, 3, 2, 1])),
+        (Orientation::FlipVertical, (3, 2, vec![4, 5, 6, 1, 2, 3])),
+        (Orientation::Transpose, (2, 3, vec![1, 4, 2, 5, 3, 6])),
+        (Orientation::Rotate90, (2, 3, vec![4, 1, 5, 2, 6, 3])),
+        (Orientation::Transverse, (2, 3, vec![6, 3, 5, 2, 4, 1])),
+        (Orientation::Rotate270, (2, 3, vec![3, 6, 2, 5, 1, 4])),
+    ];
+
+    for &(orientation, ref expected) in cases.iter() {
+        let mut image = numbered();
+        image.apply_orientation(orientation);
+        assert_eq!(&layout(&image), expected, "{:?}", orientation);
+    }
+}
+
+/// APP1 segment with a TIFF header holding only an Orientation tag
+fn exif_segment(orientation: u16, big_endian: bool) -> Vec<u8> {
+    let u16_bytes = |v: u16| if big_endian { v.to_be_bytes() } else { v.to_le_bytes() };
+    let u32_bytes = |v: u32| if big_endian { v.to_be_bytes() } else { v.to_le_bytes() };
+
+    let mut tiff = if big_endian { b"MM".to_vec() } else { b"II".to_vec() };
+    tiff.extend_from_slice(&u16_bytes(42));
+    tiff.extend_from_slice(&u32_bytes(8));
+    // One IFD entry: tag, SHORT type, count and the value padded to four bytes
+    tiff.extend_from_slice(&u16_bytes(1));
+    tiff.extend_from_slice(&u16_bytes(0x0112));
+    tiff.extend_from_slice(&u16_bytes(3));
+    tiff.extend_from_slice(&u32_bytes(1));
+    tiff.extend_from_slice(&u16_bytes(orientation));
+    tiff.extend_from_slice(&[0, 0]);
+    tiff.extend_from_slice(&u32_bytes(0));
+
+    let mut segment = vec![0xFF, 0xE1];
+    segment.extend_from_slice(&(2 + 6 + tiff.len() as u16).to_be_bytes());
+    segment.extend_from_slice(b"Exif\0\0");
+    segment.extend_from_slice(&tiff);
+    segment
+}
+
+/// 16x8 JPEG with a white left half and a black right half, tagged with an orientation
+fn tagged_jpeg(orientation: u16, big_endian: bool) -> Vec<u8> {
+    let data: Vec<Color> = (0..16 * 8)
+        .map(|i| if i % 16 < 8 { Color::rgb(255, 255, 255) } else { Color::rgb(0, 0, 0) })
+        .collect();
+    let image = Image::from_data(16, 8, data.into_boxed_slice()).unwrap();
+    let mut jpeg = Vec::new();
+    image.encode_jpeg(&mut jpeg, 95).unwrap();
+
+    // Right after the start of image marker
+    let exif = exif_segment(orientation, big_endian);
+    jpeg.splice(2..2, exif);
+    jpeg
+}
+
+fn is_white(image: &Image, x: i32, y: i32) -> bool {
+    image.getpixel(x, y).r() > 128
+}
+
+#[test]
+fn exif_orientation_reads_both_byte_orders() {
+    assert_eq!(exif_orientation(&tagged_jpeg(6, false)), Some(6));
+    assert_eq!(exif_orientation(&tagged_jpeg(3, true)), Some(3));
+    assert_eq!(exif_orientation(b"not a jpeg"), None);
+}
+
+#[test]
+fn loading_applies_orientation() {
+    // Rotating clockwise moves the white left half to the top
+    let image = Image::from_bytes(&tagged_jpeg(6, false)).unwrap();
+    assert_eq!((image.width(), image.height()), (8, 16));
+    assert!(is_white(&image, 4, 2));
+    assert!(!is_white(&image, 4, 13));
+
+    let image = Image::from_bytes(&tagged_jpeg(3, true)).unwrap();
+    assert_eq!((image.width(), image.height()), (16, 8));
+    assert!(!is_white(&image, 2, 4));
+    assert!(is_white(&image, 13, 4));
+}
+
+#[test]
+fn loading_can_keep_stored_orientation() {
+    let options = LoadOptions {
+        apply_orientation: false,
+    };
+    let image = Image::from_bytes_with_options(&tagged_jpeg(6, false), &options).unwrap();
+    assert_eq!((image.width(), image.height()), (16, 8));
+    assert!(is_white(&image, 2, 4));
+    assert!(!is_white(&image, 13, 4));
+}
@@ -0,0 +1,35 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::{Image, ResizeType};
+
+#[test]
+fn averages_in_linear_light() {
+    let data = vec![Color::rgb(0, 0, 0), Color::rgb(255, 255, 255)];
+    let image = Image::from_data(2, 1, data.into_boxed_slice()).unwrap();
+
+    let srgb = image.resize(1, 1, ResizeType::Triangle).unwrap();
+    let linear = image.resize_linear(1, 1, ResizeType::Triangle).unwrap();
+
+    // Half the light of white is encoded as 188, not 128
+    assert_eq!(srgb.data()[0].data & 0xFF, 128);
+    assert_eq!(linear.data()[0].data, 0xFFBCBCBC);
+}
+
+#[test]
+fn uniform_colors_unchanged() {
+    for &value in &[0u8, 1, 2, 10, 64, 128, 200, 254, 255] {
+        let color = Color::rgba(value, 255 - value, value / 2, 255);
+        let image = Image::from_color(5, 3, color);
+        let resized = image.resize_linear(2, 7, ResizeType::Lanczos3).unwrap();
+        for pixel in resized.data() {
+            assert_eq!(pixel.data, color.data);
+        }
+    }
+}
+
+#[test]
+fn empty_image_fails() {
+    assert!(Image::new(0, 0).resize_linear(4, 4, ResizeType::Point).is_err());
+}
@@ -0,0 +1,96 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use std::fs;
+use std::io::Cursor;
+
+use orbclient::{Color, Renderer};
+use orbimage::{Error, Image, ImageFormat};
+
+fn sample() -> Image {
+    let mut data = Vec::new();
+    for y in 0..4u8 {
+        for x in 0..5u8 {
+            data.push(Color::rgba(x * 50, y * 60, 200, 255));
+        }
+    }
+    Image::from_data(5, 4, data.into_boxed_slice()).unwrap()
+}
+
+fn png_bytes() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    sample().encode_png(&mut bytes).unwrap();
+    bytes
+}
+
+fn raw(image: &Image) -> Vec<u32> {
+    image.data().iter().map(|c| c.data).collect()
+}
+
+#[test]
+fn from_magic_detects_formats() {
+    assert_eq!(ImageFormat::from_magic(b"\x89PNG\r\n\x1a\n...."), Some(ImageFormat::Png));
+    assert_eq!(ImageFormat::from_magic(&[0xFF, 0xD8, 0xFF, 0xE0]), Some(ImageFormat::Jpeg));
+    assert_eq!(ImageFormat::from_magic(b"GIF89a"), Some(ImageFormat::Gif));
+    assert_eq!(ImageFormat::from_magic(b"BM...."), Some(ImageFormat::Bmp));
+    assert_eq!(ImageFormat::from_magic(b"RIFF\0\0\0\0WEBP"), Some(ImageFormat::Webp));
+    assert_eq!(ImageFormat::from_magic(b"P6\n"), Some(ImageFormat::Pnm));
+    assert_eq!(ImageFormat::from_magic(b"RIFF\0\0\0\0WAVE"), None);
+    assert_eq!(ImageFormat::from_magic(b""), None);
+}
+
+#[test]
+fn from_extension_ignores_case() {
+    assert_eq!(ImageFormat::from_extension("JPG"), Some(ImageFormat::Jpeg));
+    assert_eq!(ImageFormat::from_extension("apng"), Some(ImageFormat::Png));
+    assert_eq!(ImageFormat::from_extension("tga"), Some(ImageFormat::Tga));
+    assert_eq!(ImageFormat::from_extension("txt"), None);
+}
+
+#[test]
+fn from_bytes_detects_png_bmp_and_jpeg() {
+    let image = sample();
+    assert_eq!(raw(&Image::from_bytes(&png_bytes()).unwrap()), raw(&image));
+
+    let mut bmp = Vec::new();
+    image.encode_bmp(&mut bmp).unwrap();
+    assert_eq!(raw(&Image::from_bytes(&bmp).unwrap()), raw(&image));
+
+    let mut jpeg = Vec::new();
+    image.encode_jpeg(&mut jpeg, 90).unwrap();
+    let decoded = Image::from_bytes(&jpeg).unwrap();
+    assert_eq!((decoded.width(), decoded.height()), (5, 4));
+}
+
+#[test]
+fn from_path_trusts_contents_over_extension() {
+    let path = std::env::temp_dir().join(format!("orbimage-misnamed-{}.jpg", std::process::id()));
+    fs::write(&path, png_bytes()).unwrap();
+    let loaded = Image::from_path(&path);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(raw(&loaded.unwrap()), raw(&sample()));
+}
+
+#[test]
+fn unknown_magic_is_unsupported() {
+    match Image::from_bytes(b"not an image at all") {
+        Err(Error::UnsupportedFormat(_)) => (),
+        other => panic!("expected UnsupportedFormat, got {:?}", other.err()),
+    }
+}
+
+#[test]
+fn from_reader_starts_at_current_position() {
+    let mut bytes = b"junk".to_vec();
+    bytes.extend_from_slice(&png_bytes());
+    let mut cursor = Cursor::new(bytes);
+    cursor.set_position(4);
+    assert_eq!(raw(&Image::from_reader(cursor).unwrap()), raw(&sample()));
+}
+
+#[test]
+fn from_reader_with_format_skips_detection() {
+    let image = Image::from_reader_with_format(Cursor::new(png_bytes()), ImageFormat::Png).unwrap();
+    assert_eq!(raw(&image), raw(&sample()));
+    assert!(Image::from_reader_with_format(Cursor::new(png_bytes()), ImageFormat::Bmp).is_err());
+}
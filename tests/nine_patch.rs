@@ -0,0 +1,94 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::{Image, Insets, NinePatch, PatchMode};
+
+/// A 3x3 image with a distinct color per piece
+fn grid() -> Image {
+    let data: Vec<Color> = (1..10).map(|i| Color::rgba(i * 10, 0, 0, 128)).collect();
+    Image::from_data(3, 3, data.into_boxed_slice()).unwrap()
+}
+
+fn reds(image: &Image) -> Vec<u8> {
+    image.data().iter().map(|c| c.r()).collect()
+}
+
+fn insets(left: u32, top: u32, right: u32, bottom: u32) -> Insets {
+    Insets { left, top, right, bottom }
+}
+
+#[test]
+fn render_stretches_middle() {
+    let patch = NinePatch::new(grid(), insets(1, 1, 1, 1));
+    let image = patch.render(5, 4);
+
+    assert_eq!((image.width(), image.height()), (5, 4));
+    assert_eq!(reds(&image), vec![
+        10, 20, 20, 20, 30,
+        40, 50, 50, 50, 60,
+        40, 50, 50, 50, 60,
+        70, 80, 80, 80, 90,
+    ]);
+    // Copied, not blended with the transparent background
+    assert_eq!(image.data()[0].data, Color::rgba(10, 0, 0, 128).data);
+}
+
+#[test]
+fn render_tiles() {
+    let data: Vec<Color> = (1..5).map(|i| Color::rgb(i * 10, 0, 0)).collect();
+    let image = Image::from_data(4, 1, data.into_boxed_slice()).unwrap();
+    let patch = NinePatch::new(image, insets(1, 0, 1, 0)).edges(PatchMode::Tile).center(PatchMode::Tile);
+
+    assert_eq!(reds(&patch.render(7, 1)), vec![10, 20, 30, 20, 30, 20, 40]);
+}
+
+#[test]
+fn shrinks_corners() {
+    let patch = NinePatch::new(grid(), insets(1, 1, 1, 1));
+    let image = patch.render(2, 2);
+    assert_eq!(reds(&image), vec![10, 30, 70, 90]);
+}
+
+#[test]
+fn guides() {
+    let black = Color::rgb(0, 0, 0);
+    let clear = Color::rgba(0, 0, 0, 0);
+    let white = Color::rgb(255, 255, 255);
+    // 4x3 content inside a guide border, stretching the second column and middle row
+    let mut image = Image::from_color(6, 5, white);
+    for i in 0..6 {
+        image.data_mut()[i] = clear;
+        image.data_mut()[24 + i] = clear;
+    }
+    for y in 0..5 {
+        image.data_mut()[y * 6] = clear;
+        image.data_mut()[y * 6 + 5] = clear;
+    }
+    image.data_mut()[2] = black;
+    image.data_mut()[2 * 6] = black;
+    // Content padding
+    image.data_mut()[24 + 2] = black;
+    image.data_mut()[24 + 3] = black;
+    image.data_mut()[6 + 5] = black;
+
+    let patch = NinePatch::from_guides(image).unwrap();
+    assert_eq!((patch.image().width(), patch.image().height()), (4, 3));
+    assert_eq!(patch.insets(), insets(1, 1, 2, 1));
+    assert_eq!(patch.padding(), Some(insets(1, 0, 1, 2)));
+}
+
+#[test]
+fn missing_guides() {
+    assert!(NinePatch::from_guides(Image::from_color(5, 5, Color::rgb(255, 255, 255))).is_err());
+    assert!(NinePatch::from_guides(Image::new(2, 2)).is_err());
+}
+
+#[test]
+fn render_leaves_empty_middle_transparent() {
+    // No middle column to stretch
+    let patch = NinePatch::new(grid(), insets(2, 0, 1, 0));
+    let image = patch.render(5, 3);
+    assert_eq!(image.data()[2].data, 0);
+    assert_eq!(image.data()[4].r(), 30);
+}
@@ -0,0 +1,63 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::{Affine, Homography, Image, Interpolation};
+
+fn close(a: (f64, f64), b: (f64, f64)) -> bool {
+    (a.0 - b.0).abs() < 1e-6 && (a.1 - b.1).abs() < 1e-6
+}
+
+#[test]
+fn solver_maps_points() {
+    let src = [(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (0.0, 3.0)];
+    let dst = [(1.0, 1.0), (9.0, 2.0), (7.0, 8.0), (2.0, 6.0)];
+    let homography = Homography::from_points(src, dst).unwrap();
+    for (&s, &d) in src.iter().zip(dst.iter()) {
+        assert!(close(homography.apply(s.0, s.1).unwrap(), d));
+    }
+
+    let inverse = homography.invert().unwrap();
+    assert!(close(inverse.apply(7.0, 8.0).unwrap(), (4.0, 3.0)));
+}
+
+#[test]
+fn solver_rejects_degenerate() {
+    let src = [(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (0.0, 1.0)];
+    assert!(Homography::from_points(src, src).is_none());
+}
+
+#[test]
+fn rectangle_matches_affine() {
+    let data: Vec<Color> = (0..16).map(|i| Color::rgb(i * 10, 0, 0)).collect();
+    let image = Image::from_data(4, 4, data.into_boxed_slice()).unwrap();
+
+    let warped = image.warp_perspective([(2.0, 1.0), (10.0, 1.0), (10.0, 9.0), (2.0, 9.0)], 12, 10, Interpolation::Bilinear);
+    let scaled = image.transform(&Affine::scale(2.0, 2.0).then(&Affine::translate(2.0, 1.0)), 12, 10, Interpolation::Bilinear);
+
+    // Inside, both agree. The affine version fades the outer half pixel, the warp does not
+    for y in 2..8 {
+        for x in 3..9 {
+            let i = y * 12 + x;
+            assert_eq!(warped.data()[i].data, scaled.data()[i].data, "{}, {}", x, y);
+        }
+    }
+    assert_eq!(warped.data()[12 + 2].a(), 255);
+    assert_eq!(warped.data()[0].data, 0);
+}
+
+#[test]
+fn anti_aliased_edges() {
+    let image = Image::from_color(8, 8, Color::rgb(255, 255, 255));
+    // A trapezoid with slanted sides
+    let warped = image.warp_perspective([(4.0, 0.0), (12.0, 0.0), (16.0, 8.0), (0.0, 8.0)], 16, 8, Interpolation::Nearest);
+
+    let row: Vec<u8> = (0..16).map(|x| warped.data()[4 * 16 + x].a()).collect();
+    assert_eq!(row[0], 0);
+    assert_eq!(row[8], 255);
+    assert!(row.iter().any(|&a| a > 0 && a < 255));
+    // Edges fade alpha, not color
+    for pixel in warped.data().iter().filter(|c| c.a() > 0) {
+        assert_eq!(pixel.data & 0xFFFFFF, 0xFFFFFF);
+    }
+}
@@ -0,0 +1,57 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::Image;
+
+fn image(w: u32, values: &[u32]) -> Image {
+    let data: Vec<Color> = values.iter().map(|&v| Color { data: v }).collect();
+    Image::from_data(w, values.len() as u32 / w, data.into_boxed_slice()).unwrap()
+}
+
+fn raw(image: &Image) -> Vec<u32> {
+    image.data().iter().map(|c| c.data).collect()
+}
+
+#[test]
+fn nearest() {
+    let scaled = image(2, &[1, 2, 3, 4]).scale_nearest(2).unwrap();
+    assert_eq!((scaled.width(), scaled.height()), (4, 4));
+    assert_eq!(raw(&scaled), vec![
+        1, 1, 2, 2,
+        1, 1, 2, 2,
+        3, 3, 4, 4,
+        3, 3, 4, 4,
+    ]);
+    assert_eq!(raw(&image(2, &[1, 2]).scale_nearest(1).unwrap()), vec![1, 2]);
+    assert_eq!(image(2, &[1, 2]).scale_nearest(0).unwrap().width(), 0);
+    assert!(image(2, &[1, 2]).scale_nearest(u32::MAX).is_err());
+}
+
+#[test]
+fn scale2x_rounds_diagonals() {
+    // Both diagonals of a checkerboard get their corners filled in
+    let scaled = image(2, &[1, 0, 0, 1]).scale2x().unwrap();
+    assert_eq!(raw(&scaled), vec![
+        1, 1, 0, 0,
+        1, 0, 1, 0,
+        0, 1, 0, 1,
+        0, 0, 1, 1,
+    ]);
+}
+
+#[test]
+fn flat_areas_unchanged() {
+    let flat = image(2, &[5, 5, 5, 5]);
+    assert_eq!(raw(&flat.scale2x().unwrap()), vec![5; 16]);
+    assert_eq!(raw(&flat.scale3x().unwrap()), vec![5; 36]);
+    assert_eq!(raw(&flat.scale4x().unwrap()), vec![5; 64]);
+}
+
+#[test]
+fn scale3x_size() {
+    let scaled = image(3, &[1, 2, 3, 4, 5, 6]).scale3x().unwrap();
+    assert_eq!((scaled.width(), scaled.height()), (9, 6));
+    // Isolated pixels keep their color in the middle of their block
+    assert_eq!(scaled.data()[4 * 9 + 4].data, 5);
+}
@@ -0,0 +1,186 @@
+extern crate gif;
+extern crate image;
+extern crate orbclient;
+extern crate orbimage;
+
+mod common;
+
+use std::borrow::Cow;
+use std::fs;
+use std::io::Cursor;
+
+use image::ColorType;
+use orbclient::Color;
+use orbimage::{probe, probe_bytes, probe_reader, Error, Image, ImageFormat, ImageInfo};
+
+/// Encode a PNG of the given color type and insert a chunk right after its header
+fn png_with_chunk(width: u32, height: u32, color: ColorType, chunk: Option<(&[u8], &[u8])>) -> Vec<u8> {
+    let bytes_per_pixel = match color {
+        ColorType::RGB(bits) => 3 * bits as usize / 8,
+        ColorType::RGBA(bits) => 4 * bits as usize / 8,
+        _ => unimplemented!(),
+    };
+    let len = width as usize * height as usize * bytes_per_pixel;
+    let mut png = Vec::new();
+    image::png::PNGEncoder::new(&mut png).encode(&vec![0; len], width, height, color).unwrap();
+
+    if let Some((kind, data)) = chunk {
+        common::insert_chunk(&mut png, kind, data);
+    }
+    png
+}
+
+fn gif_bytes(frames: u16, transparent: Option<u8>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = gif::Encoder::new(&mut bytes, 4, 3, &[0, 0, 0, 255, 255, 255]).unwrap();
+        for _ in 0..frames {
+            let frame = gif::Frame {
+                width: 4,
+                height: 3,
+                transparent,
+                buffer: Cow::Owned(vec![1; 12]),
+                ..gif::Frame::default()
+            };
+            encoder.write_frame(&frame).unwrap();
+        }
+    }
+    bytes
+}
+
+fn info(width: u32, height: u32, format: ImageFormat, has_alpha: bool, bit_depth: u8, frame_count: u32) -> ImageInfo {
+    ImageInfo {
+        width,
+        height,
+        format,
+        has_alpha,
+        bit_depth,
+        frame_count,
+    }
+}
+
+#[test]
+fn probe_png_header() {
+    let rgba = png_with_chunk(5, 3, ColorType::RGBA(8), None);
+    assert_eq!(probe_bytes(&rgba).unwrap(), info(5, 3, ImageFormat::Png, true, 8, 1));
+
+    let rgb16 = png_with_chunk(7, 2, ColorType::RGB(16), None);
+    assert_eq!(probe_bytes(&rgb16).unwrap(), info(7, 2, ImageFormat::Png, false, 16, 1));
+}
+
+#[test]
+fn probe_png_transparency_chunk() {
+    let trns = png_with_chunk(2, 2, ColorType::RGB(8), Some((b"tRNS", &[0, 0, 0, 0, 0, 0])));
+    assert_eq!(probe_bytes(&trns).unwrap(), info(2, 2, ImageFormat::Png, true, 8, 1));
+}
+
+#[test]
+fn probe_apng_frame_count() {
+    let apng = png_with_chunk(2, 2, ColorType::RGB(8), Some((b"acTL", &[0, 0, 0, 3, 0, 0, 0, 0])));
+    assert_eq!(probe_bytes(&apng).unwrap().frame_count, 3);
+}
+
+#[test]
+fn probe_gif_frames_and_transparency() {
+    assert_eq!(probe_bytes(&gif_bytes(1, None)).unwrap(), info(4, 3, ImageFormat::Gif, false, 8, 1));
+    assert_eq!(probe_bytes(&gif_bytes(3, Some(0))).unwrap(), info(4, 3, ImageFormat::Gif, true, 8, 3));
+}
+
+#[test]
+fn probe_jpeg_and_bmp() {
+    let image = Image::from_color(6, 4, Color::rgba(10, 20, 30, 128));
+    let mut jpeg = Vec::new();
+    image.encode_jpeg(&mut jpeg, 80).unwrap();
+    assert_eq!(probe_bytes(&jpeg).unwrap(), info(6, 4, ImageFormat::Jpeg, false, 8, 1));
+
+    let mut bmp = Vec::new();
+    image.encode_bmp(&mut bmp).unwrap();
+    let bmp = probe_bytes(&bmp).unwrap();
+    assert_eq!((bmp.width, bmp.height, bmp.format), (6, 4, ImageFormat::Bmp));
+}
+
+#[test]
+fn probe_reader_and_path() {
+    let png = png_with_chunk(3, 1, ColorType::RGBA(8), None);
+    assert_eq!(probe_reader(Cursor::new(&png)).unwrap().width, 3);
+
+    // Contents win over a wrong extension
+    let path = std::env::temp_dir().join(format!("orbimage-probe-{}.gif", std::process::id()));
+    fs::write(&path, &png).unwrap();
+    let probed = probe(&path);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(probed.unwrap().format, ImageFormat::Png);
+}
+
+#[test]
+fn probe_unknown_data() {
+    match probe_bytes(b"plain text") {
+        Err(Error::UnsupportedFormat(_)) => (),
+        other => panic!("expected UnsupportedFormat, got {:?}", other),
+    }
+    assert!(probe_bytes(b"\x89PNG\r\n\x1a\n\0\0").is_err());
+}
+
+/// A RIFF WebP container holding the given chunks
+fn webp_bytes(chunks: &[(&[u8], Vec<u8>)]) -> Vec<u8> {
+    let mut body = b"WEBP".to_vec();
+    for &(kind, ref data) in chunks {
+        body.extend_from_slice(kind);
+        body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        body.extend_from_slice(data);
+        if data.len() % 2 == 1 {
+            body.push(0);
+        }
+    }
+    let mut webp = b"RIFF".to_vec();
+    webp.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    webp.extend_from_slice(&body);
+    webp
+}
+
+fn vp8x(flags: u8, width: u32, height: u32) -> Vec<u8> {
+    let mut data = vec![flags, 0, 0, 0];
+    data.extend_from_slice(&(width - 1).to_le_bytes()[..3]);
+    data.extend_from_slice(&(height - 1).to_le_bytes()[..3]);
+    data
+}
+
+#[test]
+fn probe_webp_lossy() {
+    let mut vp8 = vec![0x10, 0x02, 0x00, 0x9D, 0x01, 0x2A];
+    vp8.extend_from_slice(&300u16.to_le_bytes());
+    vp8.extend_from_slice(&200u16.to_le_bytes());
+    vp8.extend_from_slice(&[0; 7]);
+    let webp = webp_bytes(&[(b"VP8 ", vp8)]);
+    assert_eq!(probe_bytes(&webp).unwrap(), info(300, 200, ImageFormat::Webp, false, 8, 1));
+}
+
+#[test]
+fn probe_webp_lossless_alpha() {
+    let vp8l = |alpha: u32| {
+        let bits: u32 = 4 | (6 << 14) | (alpha << 28);
+        let mut data = vec![0x2F];
+        data.extend_from_slice(&bits.to_le_bytes());
+        data
+    };
+    let opaque = webp_bytes(&[(b"VP8L", vp8l(0))]);
+    assert_eq!(probe_bytes(&opaque).unwrap(), info(5, 7, ImageFormat::Webp, false, 8, 1));
+    let alpha = webp_bytes(&[(b"VP8L", vp8l(1))]);
+    assert_eq!(probe_bytes(&alpha).unwrap(), info(5, 7, ImageFormat::Webp, true, 8, 1));
+}
+
+#[test]
+fn probe_webp_extended() {
+    let still = webp_bytes(&[(b"VP8X", vp8x(0, 640, 480)), (b"VP8 ", vec![0; 20])]);
+    assert_eq!(probe_bytes(&still).unwrap(), info(640, 480, ImageFormat::Webp, false, 8, 1));
+
+    // Alpha and animation flags, with odd sized frames to check chunk padding
+    let animated = webp_bytes(&[
+        (b"VP8X", vp8x(0x12, 70_000, 3)),
+        (b"ANIM", vec![0; 6]),
+        (b"ANMF", vec![0; 3]),
+        (b"ANMF", vec![0; 3]),
+        (b"ANMF", vec![0; 3]),
+    ]);
+    assert_eq!(probe_bytes(&animated).unwrap(), info(70_000, 3, ImageFormat::Webp, true, 8, 3));
+}
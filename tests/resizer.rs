@@ -0,0 +1,71 @@
+extern crate orbclient;
+extern crate orbimage;
+extern crate resize;
+
+use orbclient::{Color, Renderer};
+use orbimage::{Image, ResizeType, Resizer};
+use resize::Pixel;
+
+fn gradient(w: u32, h: u32) -> Image {
+    let data: Vec<Color> = (0..w * h).map(|i| Color::rgb((i * 7) as u8, (i * 3) as u8, i as u8)).collect();
+    Image::from_data(w, h, data.into_boxed_slice()).unwrap()
+}
+
+fn raw(image: &Image) -> Vec<u32> {
+    image.data().iter().map(|c| c.data).collect()
+}
+
+/// Resize opaque pixels with the resize crate directly, so there is nothing to premultiply
+fn direct(image: &Image, w: u32, h: u32, resize_type: ResizeType) -> Vec<u32> {
+    let src: Vec<u8> = image.data().iter().flat_map(|c| c.data.to_le_bytes()).collect();
+    let mut dst = vec![0; w as usize * h as usize * 4];
+    resize::new(image.width() as usize, image.height() as usize, w as usize, h as usize, Pixel::RGBA, resize_type)
+        .resize(&src, &mut dst);
+    dst.chunks(4).map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]])).collect()
+}
+
+#[test]
+fn matches_resize_crate() {
+    let image = gradient(8, 6);
+    let mut resizer = Resizer::new(8, 6, 5, 3, ResizeType::Lanczos3).unwrap();
+    assert_eq!(resizer.source_size(), (8, 6));
+    assert_eq!(resizer.destination_size(), (5, 3));
+
+    let expected = direct(&image, 5, 3, ResizeType::Lanczos3);
+    assert_eq!(raw(&resizer.resize(&image).unwrap()), expected);
+    // Reusing the plan gives the same result
+    assert_eq!(raw(&resizer.resize(&image).unwrap()), expected);
+}
+
+#[test]
+fn resize_into_reuses_buffer() {
+    let image = gradient(8, 6);
+    let mut resizer = Resizer::new(8, 6, 4, 4, ResizeType::Triangle).unwrap();
+
+    let mut dst = Image::new(4, 4);
+    let ptr = dst.data().as_ptr();
+    resizer.resize_into(&image, &mut dst).unwrap();
+    assert_eq!(dst.data().as_ptr(), ptr);
+    assert_eq!(raw(&dst), direct(&image, 4, 4, ResizeType::Triangle));
+
+    // A destination of another size is reshaped
+    let mut dst = Image::new(1, 1);
+    resizer.resize_into(&image, &mut dst).unwrap();
+    assert_eq!((dst.width(), dst.height()), (4, 4));
+}
+
+#[test]
+fn wrong_source_size() {
+    let mut resizer = Resizer::new(8, 6, 4, 4, ResizeType::Point).unwrap();
+    assert!(resizer.resize(&gradient(6, 8)).is_err());
+}
+
+#[test]
+fn linear() {
+    // Averaging black and white in linear light gives about 188 in sRGB, not 128
+    let image = Image::from_data(2, 1, vec![Color::rgb(0, 0, 0), Color::rgb(255, 255, 255)].into_boxed_slice()).unwrap();
+    let mut resizer = Resizer::new_linear(2, 1, 1, 1, ResizeType::Triangle).unwrap();
+    let gray = resizer.resize(&image).unwrap().data()[0];
+    assert!((187..=188).contains(&gray.r()), "got {}", gray.r());
+    assert_eq!((gray.r(), gray.a()), (gray.b(), 255));
+}
@@ -0,0 +1,159 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use std::cell::Cell;
+
+use orbclient::{Color, Mode, Renderer};
+use orbimage::Image;
+
+/// A renderer that draws into memory
+struct Headless {
+    w: u32,
+    h: u32,
+    data: Vec<Color>,
+    mode: Cell<Mode>,
+}
+
+impl Headless {
+    fn new(w: u32, h: u32) -> Self {
+        Headless {
+            w,
+            h,
+            data: vec![Color { data: 0 }; (w * h) as usize],
+            mode: Cell::new(Mode::Blend),
+        }
+    }
+
+    fn raw(&self) -> Vec<u32> {
+        self.data.iter().map(|c| c.data).collect()
+    }
+}
+
+impl Renderer for Headless {
+    fn width(&self) -> u32 {
+        self.w
+    }
+
+    fn height(&self) -> u32 {
+        self.h
+    }
+
+    fn data(&self) -> &[Color] {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut [Color] {
+        &mut self.data
+    }
+
+    fn sync(&mut self) -> bool {
+        true
+    }
+
+    fn update(&mut self) -> bool {
+        true
+    }
+
+    fn update_rects(&mut self, _rects: &[(i32, i32, u32, u32)]) -> bool {
+        true
+    }
+
+    fn mode(&self) -> &Cell<Mode> {
+        &self.mode
+    }
+}
+
+/// A 4x4 opaque image whose pixels are numbered 1 to 16 in reading order
+fn numbered() -> Image {
+    let data: Vec<Color> = (1..17).map(|i| Color { data: 0xFF000000 | i }).collect();
+    Image::from_data(4, 4, data.into_boxed_slice()).unwrap()
+}
+
+/// Pixel numbers of a drawn renderer, with 0 for untouched pixels
+fn numbers(renderer: &Headless) -> Vec<u32> {
+    renderer.raw().iter().map(|c| c & 0xFFFFFF).collect()
+}
+
+#[test]
+fn draw_inside() {
+    let image = numbered();
+    let mut renderer = Headless::new(4, 3);
+    image.roi(1, 1, 2, 2).draw(&mut renderer, 1, 1);
+    assert_eq!(numbers(&renderer), vec![
+        0, 0, 0, 0,
+        0, 6, 7, 0,
+        0, 10, 11, 0,
+    ]);
+}
+
+#[test]
+fn draw_negative_position() {
+    let image = numbered();
+    let mut renderer = Headless::new(3, 3);
+    image.roi(0, 0, 3, 3).draw(&mut renderer, -1, -2);
+    assert_eq!(numbers(&renderer), vec![
+        10, 11, 0,
+        0, 0, 0,
+        0, 0, 0,
+    ]);
+}
+
+#[test]
+fn draw_past_right_and_bottom() {
+    let image = numbered();
+    let mut renderer = Headless::new(3, 3);
+    image.roi(1, 0, 3, 4).draw(&mut renderer, 1, 1);
+    assert_eq!(numbers(&renderer), vec![
+        0, 0, 0,
+        0, 2, 3,
+        0, 6, 7,
+    ]);
+}
+
+#[test]
+fn draw_does_not_read_past_row() {
+    let image = numbered();
+    let mut renderer = Headless::new(4, 2);
+    image.roi(2, 0, 1, 2).draw(&mut renderer, 0, 0);
+    assert_eq!(numbers(&renderer), vec![
+        3, 0, 0, 0,
+        7, 0, 0, 0,
+    ]);
+}
+
+#[test]
+fn draw_off_screen() {
+    let image = numbered();
+    let mut renderer = Headless::new(3, 3);
+    for &(x, y) in &[(-4, 0), (0, -4), (3, 0), (0, 3), (i32::MIN, i32::MIN), (i32::MAX, i32::MAX)] {
+        image.roi(0, 0, 4, 4).draw(&mut renderer, x, y);
+    }
+    assert_eq!(numbers(&renderer), vec![0; 9]);
+}
+
+#[test]
+fn draw_blends_and_overwrites() {
+    let image = Image::from_color(1, 1, Color::rgba(255, 255, 255, 0));
+    let mut renderer = Headless::new(1, 1);
+    renderer.data[0] = Color::rgb(10, 20, 30);
+
+    image.roi(0, 0, 1, 1).draw(&mut renderer, 0, 0);
+    assert_eq!(renderer.raw(), vec![0xFF0A141E]);
+
+    renderer.mode().set(Mode::Overwrite);
+    image.roi(0, 0, 1, 1).draw(&mut renderer, 0, 0);
+    assert_eq!(renderer.raw(), vec![0x00FFFFFF]);
+}
+
+#[test]
+fn draw_into_roi_mut() {
+    let image = numbered();
+    let mut target = Image::from_color(4, 4, Color { data: 0 });
+    image.roi(0, 0, 2, 2).draw(&mut target.roi_mut(1, 1, 2, 2), 1, 0);
+    assert_eq!(target.data().iter().map(|c| c.data & 0xFFFFFF).collect::<Vec<_>>(), vec![
+        0, 0, 0, 0,
+        0, 0, 1, 0,
+        0, 0, 5, 0,
+        0, 0, 0, 0,
+    ]);
+}
@@ -0,0 +1,109 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Mode, Renderer};
+use orbimage::Image;
+
+/// A 6x5 opaque image whose pixels are numbered 1 to 30 in reading order
+fn numbered() -> Image {
+    let data: Vec<Color> = (1..31).map(|i| Color { data: 0xFF000000 | i }).collect();
+    Image::from_data(6, 5, data.into_boxed_slice()).unwrap()
+}
+
+/// Pixel numbers of an image, with pixels drawn in `MARK` reported as 0
+fn marked(image: &Image) -> Vec<u32> {
+    image.data().iter().map(|c| if c.data == MARK { 0 } else { c.data & 0xFFFFFF }).collect()
+}
+
+const MARK: u32 = 0xFFABCDEF;
+
+#[test]
+fn roi_mut_pixel_is_clipped() {
+    let mut image = numbered();
+    {
+        let mut roi = image.roi_mut(1, 1, 3, 2);
+        for &(x, y) in &[(0, 0), (2, 1), (-1, 0), (0, -1), (3, 0), (0, 2), (i32::MIN, i32::MAX)] {
+            roi.pixel(x, y, Color { data: MARK });
+        }
+    }
+    assert_eq!(marked(&image), vec![
+        1, 2, 3, 4, 5, 6,
+        7, 0, 9, 10, 11, 12,
+        13, 14, 15, 0, 17, 18,
+        19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30,
+    ]);
+}
+
+#[test]
+fn roi_mut_rect_and_set_are_clipped() {
+    let mut image = numbered();
+    image.roi_mut(1, 1, 3, 2).rect(-5, 1, 100, 100, Color { data: MARK });
+    assert_eq!(marked(&image), vec![
+        1, 2, 3, 4, 5, 6,
+        7, 8, 9, 10, 11, 12,
+        13, 0, 0, 0, 17, 18,
+        19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30,
+    ]);
+
+    let mut image = numbered();
+    image.roi_mut(4, 3, 10, 10).set(Color { data: MARK });
+    assert_eq!(marked(&image), vec![
+        1, 2, 3, 4, 5, 6,
+        7, 8, 9, 10, 11, 12,
+        13, 14, 15, 16, 17, 18,
+        19, 20, 21, 22, 0, 0,
+        25, 26, 27, 28, 0, 0,
+    ]);
+}
+
+#[test]
+fn roi_mut_rect_blends_unless_overwriting() {
+    let mut image = Image::from_color(3, 1, Color::rgb(0, 0, 0));
+    image.roi_mut(1, 0, 1, 1).rect(0, 0, 1, 1, Color::rgba(255, 255, 255, 0));
+    assert_eq!(image.getpixel(1, 0).data, 0xFF000000);
+
+    image.mode().set(Mode::Overwrite);
+    image.roi_mut(1, 0, 1, 1).rect(-1, 0, 3, 1, Color::rgba(255, 255, 255, 0));
+    assert_eq!(image.data().iter().map(|c| c.data).collect::<Vec<_>>(), vec![0xFF000000, 0x00FFFFFF, 0xFF000000]);
+}
+
+#[test]
+fn roi_mut_box_blur_stays_inside() {
+    // A uniform gray ROI surrounded by white
+    let mut image = Image::from_color(8, 8, Color::rgb(255, 255, 255));
+    image.roi_mut(2, 2, 4, 4).set(Color::rgb(100, 100, 100));
+    let before = image.clone();
+
+    image.roi_mut(2, 2, 4, 4).box_blur(-2, -2, 20, 20, 1);
+    // Neither the white border leaks in nor the gray leaks out
+    assert_eq!(image.data().iter().map(|c| c.data).collect::<Vec<_>>(),
+               before.data().iter().map(|c| c.data).collect::<Vec<_>>());
+}
+
+#[test]
+fn roi_mut_image_over_writes_whole_rows() {
+    let mut image = numbered();
+    let rows = vec![Color { data: MARK }; 3 * 4];
+    // Starting two rows above the ROI, so only the first two rows of the ROI are written
+    image.roi_mut(2, 2, 3, 2).image_over(-2, &rows);
+    assert_eq!(marked(&image), vec![
+        1, 2, 3, 4, 5, 6,
+        7, 8, 9, 10, 11, 12,
+        13, 14, 0, 0, 0, 18,
+        19, 20, 0, 0, 0, 24,
+        25, 26, 27, 28, 29, 30,
+    ]);
+}
+
+#[test]
+fn roi_mut_getpixel_is_relative_and_clipped() {
+    let mut image = numbered();
+    let roi = image.roi_mut(2, 1, 2, 2);
+    assert_eq!(roi.getpixel(0, 0).data, 0xFF000009);
+    assert_eq!(roi.getpixel(1, 1).data, 0xFF000010);
+    for &(x, y) in &[(-1, 0), (0, -1), (2, 0), (0, 2), (i32::MAX, i32::MAX)] {
+        assert_eq!(roi.getpixel(x, y).data, 0);
+    }
+}
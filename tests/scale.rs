@@ -0,0 +1,102 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::{Image, Placement, ResizeType, Resizer, ScaleMode};
+
+fn place(image: &Image, w: u32, h: u32, mode: ScaleMode) -> (u32, u32, Placement) {
+    let (scaled, place) = image.scale_to(w, h, mode, ResizeType::Point).unwrap();
+    (scaled.width(), scaled.height(), place)
+}
+
+#[test]
+fn scaled_sizes() {
+    assert_eq!(ScaleMode::Center.scaled_size(40, 20, 100, 100), (40, 20));
+    assert_eq!(ScaleMode::Tile.scaled_size(40, 20, 100, 100), (40, 20));
+    assert_eq!(ScaleMode::Fill.scaled_size(40, 20, 100, 100), (100, 100));
+    assert_eq!(ScaleMode::Scale.scaled_size(40, 20, 100, 100), (100, 50));
+    assert_eq!(ScaleMode::Zoom.scaled_size(40, 20, 100, 100), (200, 100));
+}
+
+#[test]
+fn center_crops_large_images() {
+    let image = Image::new(10, 4);
+    let (w, h, place) = place(&image, 6, 8, ScaleMode::Center);
+    assert_eq!((w, h), (10, 4));
+    assert_eq!(place, Placement { x: 0, y: 2, crop_x: 2, crop_y: 0, w: 6, h: 4 });
+}
+
+#[test]
+fn scale_letterboxes() {
+    let image = Image::new(40, 20);
+    let (w, h, place) = place(&image, 100, 100, ScaleMode::Scale);
+    assert_eq!((w, h), (100, 50));
+    assert_eq!(place, Placement { x: 0, y: 25, crop_x: 0, crop_y: 0, w: 100, h: 50 });
+}
+
+#[test]
+fn zoom_crops() {
+    let image = Image::new(40, 20);
+    let (w, h, place) = place(&image, 100, 100, ScaleMode::Zoom);
+    assert_eq!((w, h), (200, 100));
+    assert_eq!(place, Placement { x: 0, y: 0, crop_x: 50, crop_y: 0, w: 100, h: 100 });
+}
+
+#[test]
+fn tile_repeats() {
+    let data = vec![Color::rgb(1, 0, 0), Color::rgb(2, 0, 0), Color::rgb(3, 0, 0), Color::rgb(4, 0, 0)];
+    let image = Image::from_data(2, 2, data.into_boxed_slice()).unwrap();
+    let (tiled, place) = image.scale_to(3, 3, ScaleMode::Tile, ResizeType::Point).unwrap();
+
+    assert_eq!(place, Placement { x: 0, y: 0, crop_x: 0, crop_y: 0, w: 3, h: 3 });
+    let reds: Vec<u8> = tiled.data().iter().map(|c| c.r()).collect();
+    assert_eq!(reds, vec![1, 2, 1, 3, 4, 3, 1, 2, 1]);
+}
+
+fn raw(image: &Image) -> Vec<u32> {
+    image.data().iter().map(|c| c.data).collect()
+}
+
+/// A 4x2 image of black and white stripes
+fn stripes() -> Image {
+    let data: Vec<Color> = (0..8)
+        .map(|i| if i % 2 == 0 { Color::rgb(0, 0, 0) } else { Color::rgb(255, 255, 255) })
+        .collect();
+    Image::from_data(4, 2, data.into_boxed_slice()).unwrap()
+}
+
+#[test]
+fn scale_into_matches_scale_to() {
+    let image = stripes();
+    let mut resizer = None;
+    let mut scaled = Image::default();
+    for &(w, h, mode) in &[(8, 8, ScaleMode::Scale), (3, 3, ScaleMode::Zoom), (5, 5, ScaleMode::Center), (6, 3, ScaleMode::Tile)] {
+        let place = image.scale_into(w, h, mode, ResizeType::Triangle, &mut resizer, &mut scaled).unwrap();
+        let (expected, expected_place) = image.scale_to(w, h, mode, ResizeType::Triangle).unwrap();
+        assert_eq!(place, expected_place);
+        assert_eq!((scaled.width(), scaled.height()), (expected.width(), expected.height()));
+        assert_eq!(raw(&scaled), raw(&expected));
+    }
+}
+
+#[test]
+fn scale_into_replaces_resizer_planned_differently() {
+    let image = stripes();
+    let mut scaled = Image::default();
+    let expected = raw(&image.resize(8, 4, ResizeType::Triangle).unwrap());
+
+    // A linear resizer of the right sizes is replaced, so the result is filtered in sRGB
+    let mut resizer = Some(Resizer::new_linear(4, 2, 8, 4, ResizeType::Triangle).unwrap());
+    image.scale_into(8, 6, ScaleMode::Scale, ResizeType::Triangle, &mut resizer, &mut scaled).unwrap();
+    assert_eq!(raw(&scaled), expected);
+
+    // So is one with another filter
+    let mut resizer = Some(Resizer::new(4, 2, 8, 4, ResizeType::Point).unwrap());
+    image.scale_into(8, 6, ScaleMode::Scale, ResizeType::Triangle, &mut resizer, &mut scaled).unwrap();
+    assert_eq!(raw(&scaled), expected);
+
+    // And one for another scaled size
+    image.scale_into(12, 6, ScaleMode::Scale, ResizeType::Triangle, &mut resizer, &mut scaled).unwrap();
+    assert_eq!(resizer.as_ref().map(Resizer::destination_size), Some((12, 6)));
+    assert_eq!(raw(&scaled), raw(&image.resize(12, 6, ResizeType::Triangle).unwrap()));
+}
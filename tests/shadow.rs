@@ -0,0 +1,54 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::{Error, Image};
+
+fn alpha(image: &Image, x: u32, y: u32) -> u32 {
+    image.data()[(y * image.width() + x) as usize].data >> 24
+}
+
+#[test]
+fn drop_shadow_size_and_offset() {
+    let image = Image::from_color(4, 4, Color::rgb(255, 255, 255));
+    let shadow = image.drop_shadow((2, -1), 0, 0, Color::rgba(0, 0, 0, 128)).unwrap();
+
+    assert_eq!((shadow.width(), shadow.height()), (6, 5));
+    // Image sits at (0, 1), shadow moved right 2 and up 1
+    assert_eq!(alpha(&shadow, 0, 0), 0);
+    assert_eq!(alpha(&shadow, 2, 0), 128);
+    assert_eq!(alpha(&shadow, 5, 3), 128);
+    assert_eq!(alpha(&shadow, 5, 4), 0);
+    assert_eq!(shadow.data()[2].data & 0xFFFFFF, 0);
+}
+
+#[test]
+fn drop_shadow_spread_and_blur() {
+    let image = Image::from_color(2, 2, Color::rgb(255, 255, 255));
+    let shadow = image.drop_shadow((0, 0), 3, 1, Color::rgb(0, 0, 0)).unwrap();
+
+    assert_eq!((shadow.width(), shadow.height()), (10, 10));
+    // Fades from the middle to the edge
+    assert!(alpha(&shadow, 4, 4) > alpha(&shadow, 2, 4));
+    assert!(alpha(&shadow, 2, 4) > alpha(&shadow, 0, 4));
+    assert_eq!(alpha(&shadow, 0, 0), 0);
+}
+
+#[test]
+fn outer_glow_cut_out() {
+    let image = Image::from_color(2, 2, Color::rgb(255, 255, 255));
+    let glow = image.outer_glow(0, 1, Color::rgb(255, 0, 0)).unwrap();
+
+    assert_eq!((glow.width(), glow.height()), (4, 4));
+    assert_eq!(glow.data()[0].data, 0xFFFF0000);
+    assert_eq!(alpha(&glow, 1, 1), 0);
+    assert_eq!(alpha(&glow, 2, 2), 0);
+}
+
+#[test]
+fn shadow_size_overflow() {
+    let image = Image::new(2, 2);
+    assert!(matches!(image.outer_glow(u32::MAX / 2, 1, Color::rgb(0, 0, 0)), Err(Error::SizeOverflow { .. })));
+    assert!(matches!(image.drop_shadow((0, 0), u32::MAX, 1, Color::rgb(0, 0, 0)), Err(Error::SizeOverflow { .. })));
+    assert!(matches!(image.drop_shadow((i32::MIN, 0), u32::MAX / 4, 0, Color::rgb(0, 0, 0)), Err(Error::SizeOverflow { .. })));
+}
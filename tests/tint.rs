@@ -0,0 +1,55 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::{Image, TintCache};
+
+fn icon() -> Image {
+    let data = vec![
+        Color::rgba(0, 0, 0, 0),
+        Color::rgba(0, 0, 0, 255),
+        Color::rgba(200, 200, 200, 128),
+        Color::rgba(255, 255, 255, 255),
+    ];
+    Image::from_data(2, 2, data.into_boxed_slice()).unwrap()
+}
+
+fn raw(image: &Image) -> Vec<u32> {
+    image.data().iter().map(|c| c.data).collect()
+}
+
+#[test]
+fn tint_keeps_alpha() {
+    let mut image = icon();
+    image.tint(Color::rgb(0x12, 0x34, 0x56));
+    assert_eq!(raw(&image), vec![0x00123456, 0xFF123456, 0x80123456, 0xFF123456]);
+
+    let mut image = icon();
+    image.tint(Color::rgba(0x12, 0x34, 0x56, 0x80));
+    assert_eq!(raw(&image)[1], 0x80123456);
+}
+
+#[test]
+fn colorize_keeps_luminance_ends() {
+    let mut image = icon();
+    image.colorize(Color::rgb(0, 128, 255));
+    let data = raw(&image);
+
+    assert_eq!(data[1], 0xFF000000);
+    assert_eq!(data[3], 0xFFFFFFFF);
+    // The light gray pixel takes the blue hue
+    let [b, _, r, a] = data[2].to_le_bytes();
+    assert!(b > r);
+    assert_eq!(a, 128);
+}
+
+#[test]
+fn cache_reuses_copies() {
+    let mut cache = TintCache::new(icon());
+    let first = cache.tinted(Color::rgb(255, 0, 0)).data().as_ptr();
+    let second = cache.tinted(Color::rgb(255, 0, 0)).data().as_ptr();
+    assert_eq!(first, second);
+
+    assert_eq!(raw(cache.tinted(Color::rgb(0, 255, 0)))[1], 0xFF00FF00);
+    assert_eq!(raw(cache.image()), raw(&icon()));
+}
@@ -0,0 +1,110 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::Image;
+
+/// A 3x2 image whose pixels are numbered in reading order:
+///
+/// 0 1 2
+/// 3 4 5
+fn numbered() -> Image {
+    let data: Vec<Color> = (0..6).map(|i| Color { data: i }).collect();
+    Image::from_data(3, 2, data.into_boxed_slice()).unwrap()
+}
+
+fn layout(image: &Image) -> (u32, u32, Vec<u32>) {
+    (image.width(), image.height(), image.data().iter().map(|c| c.data).collect())
+}
+
+#[test]
+fn flip_horizontal() {
+    assert_eq!(layout(&numbered().flip_horizontal()), (3, 2, vec![2, 1, 0, 5, 4, 3]));
+}
+
+#[test]
+fn flip_vertical() {
+    assert_eq!(layout(&numbered().flip_vertical()), (3, 2, vec![3, 4, 5, 0, 1, 2]));
+}
+
+#[test]
+fn rotate90() {
+    assert_eq!(layout(&numbered().rotate90()), (2, 3, vec![3, 0, 4, 1, 5, 2]));
+}
+
+#[test]
+fn rotate180() {
+    assert_eq!(layout(&numbered().rotate180()), (3, 2, vec![5, 4, 3, 2, 1, 0]));
+}
+
+#[test]
+fn rotate270() {
+    assert_eq!(layout(&numbered().rotate270()), (2, 3, vec![2, 5, 1, 4, 0, 3]));
+}
+
+#[test]
+fn transpose() {
+    assert_eq!(layout(&numbered().transpose()), (2, 3, vec![0, 3, 1, 4, 2, 5]));
+}
+
+#[test]
+fn in_place_matches_owned() {
+    let image = numbered();
+
+    let mut flipped = image.clone();
+    flipped.flip_horizontal_in_place();
+    assert_eq!(layout(&flipped), layout(&image.flip_horizontal()));
+
+    let mut flipped = image.clone();
+    flipped.flip_vertical_in_place();
+    assert_eq!(layout(&flipped), layout(&image.flip_vertical()));
+
+    let mut rotated = image.clone();
+    rotated.rotate90_in_place();
+    assert_eq!(layout(&rotated), layout(&image.rotate90()));
+
+    let mut rotated = image.clone();
+    rotated.rotate180_in_place();
+    assert_eq!(layout(&rotated), layout(&image.rotate180()));
+
+    let mut rotated = image.clone();
+    rotated.rotate270_in_place();
+    assert_eq!(layout(&rotated), layout(&image.rotate270()));
+
+    let mut transposed = image.clone();
+    transposed.transpose_in_place();
+    assert_eq!(layout(&transposed), layout(&image.transpose()));
+}
+
+#[test]
+fn square_transpose_in_place() {
+    let data: Vec<Color> = (0..9).map(|i| Color { data: i }).collect();
+    let mut image = Image::from_data(3, 3, data.into_boxed_slice()).unwrap();
+    image.transpose_in_place();
+    assert_eq!(layout(&image), (3, 3, vec![0, 3, 6, 1, 4, 7, 2, 5, 8]));
+}
+
+#[test]
+fn rotations_compose() {
+    let image = numbered();
+    assert_eq!(layout(&image.rotate90().rotate90()), layout(&image.rotate180()));
+    assert_eq!(layout(&image.rotate90().rotate180()), layout(&image.rotate270()));
+    assert_eq!(layout(&image.rotate90().rotate270()), layout(&image));
+}
+
+#[test]
+fn square_rotations_in_place_keep_buffer() {
+    let data: Vec<Color> = (0..16).map(|i| Color { data: i }).collect();
+    let image = Image::from_data(4, 4, data.into_boxed_slice()).unwrap();
+
+    let mut rotated = image.clone();
+    let buffer = rotated.data().as_ptr();
+    rotated.rotate90_in_place();
+    assert_eq!(layout(&rotated), layout(&image.rotate90()));
+    assert_eq!(rotated.data().as_ptr(), buffer);
+
+    rotated.rotate270_in_place();
+    rotated.rotate270_in_place();
+    assert_eq!(layout(&rotated), layout(&image.rotate270()));
+    assert_eq!(rotated.data().as_ptr(), buffer);
+}
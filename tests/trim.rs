@@ -0,0 +1,67 @@
+extern crate orbclient;
+extern crate orbimage;
+
+use orbclient::{Color, Renderer};
+use orbimage::Image;
+
+/// A transparent 6x5 image with content in columns 1 to 3 and rows 2 to 3
+fn padded() -> Image {
+    let mut image = Image::from_color(6, 5, Color::rgba(0, 0, 0, 0));
+    image.data_mut()[2 * 6 + 1] = Color::rgb(255, 0, 0);
+    image.data_mut()[3 * 6 + 3] = Color::rgba(0, 255, 0, 10);
+    image
+}
+
+#[test]
+fn content_bounds_by_alpha() {
+    let image = padded();
+    assert_eq!(image.content_bounds(0), Some((1, 2, 3, 2)));
+    assert_eq!(image.content_bounds(10), Some((1, 2, 1, 1)));
+    assert_eq!(image.content_bounds(255), None);
+    assert_eq!(Image::new(0, 0).content_bounds(0), None);
+}
+
+#[test]
+fn content_bounds_by_color() {
+    let white = Color::rgb(255, 255, 255);
+    let mut image = Image::from_color(4, 4, white);
+    image.data_mut()[5] = Color::rgb(250, 250, 250);
+    image.data_mut()[10] = Color::rgb(0, 0, 0);
+
+    assert_eq!(image.content_bounds_on(white, 0), Some((1, 1, 2, 2)));
+    assert_eq!(image.content_bounds_on(white, 5), Some((2, 2, 1, 1)));
+    assert_eq!(Image::from_color(3, 3, white).content_bounds_on(white, 0), None);
+}
+
+#[test]
+fn trim() {
+    let image = padded();
+    let trimmed = image.trim();
+    assert_eq!((trimmed.width(), trimmed.height()), (3, 2));
+    assert_eq!(trimmed.data()[0].data, 0xFFFF0000);
+
+    let roi = image.trim_roi();
+    assert_eq!((roi.x(), roi.y(), roi.width(), roi.height()), (1, 2, 3, 2));
+
+    let empty = Image::from_color(3, 3, Color::rgba(0, 0, 0, 0)).trim();
+    assert_eq!((empty.width(), empty.height()), (0, 0));
+}
+
+#[test]
+fn trim_on_color() {
+    let white = Color::rgb(255, 255, 255);
+    let mut image = Image::from_color(5, 4, white);
+    image.data_mut()[5 + 1] = Color::rgb(250, 250, 250);
+    image.data_mut()[2 * 5 + 3] = Color::rgb(0, 0, 0);
+
+    let trimmed = image.trim_on(white, 0);
+    assert_eq!((trimmed.width(), trimmed.height()), (3, 2));
+    assert_eq!(trimmed.data()[0].data, 0xFFFAFAFA);
+    assert_eq!(trimmed.data()[5].data, 0xFF000000);
+
+    let roi = image.trim_roi_on(white, 5);
+    assert_eq!((roi.x(), roi.y(), roi.width(), roi.height()), (3, 2, 1, 1));
+
+    let empty = Image::from_color(3, 3, white).trim_on(white, 0);
+    assert_eq!((empty.width(), empty.height()), (0, 0));
+}